@@ -0,0 +1,29 @@
+// Generate Rust types for cs2 files from samples.
+//
+// cargo run --example schema -- LokomotiveFile lokomotive.cs2 ...
+
+use std::env;
+use std::fs;
+use std::process;
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let name = match args.next() {
+        Some(name) => name,
+        None => {
+            eprintln!("usage: schema <type name> <sample file>...");
+            process::exit(2);
+        }
+    };
+    let mut schema = serde_cs2::Schema::new();
+    for path in args {
+        let result = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|input| schema.add(&input).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("{}: {}", path, e);
+            process::exit(1);
+        }
+    }
+    print!("{}", schema.to_rust(&name));
+}
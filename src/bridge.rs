@@ -0,0 +1,198 @@
+// Mapping between cs2 and formats with objects and arrays like JSON or YAML.
+//
+// cs2                      | JSON / YAML
+// -------------------------|--------------------------------------------
+// block                    | object with the keys in their order
+// repeated key             | array with one element per entry
+// value                    | string, hexadecimal values of chosen keys
+//                          | optionally number
+// file header [lokomotive] | object with the only key "[lokomotive]"
+//
+// Repeated keys are grouped in the array at the position of their first
+// entry. Converting back writes one entry per array element, booleans as
+// `1` and `0` and numbers in decimal, or in hexadecimal for the keys of
+// `hex_keys`.
+// The way back with the same mapping reproduces the document except for the
+// order of interleaved repeated keys.
+//
+// The cs2 side is streamed from the parsed tree into the serializer of the
+// other format, the other way is transcoded into the cs2 `Serializer`, so
+// neither direction needs an intermediate value.
+
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde::Deserializer;
+
+use crate::error::{Error, Result};
+use crate::parse::{Entry, Node, Tree};
+
+/// Options for the mapping of cs2 values to JSON and YAML.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mapping {
+    hex_keys: Vec<String>,
+}
+
+impl Mapping {
+    pub fn new() -> Self {
+        Mapping::default()
+    }
+
+    /// Map hexadecimal values like `0x4006` of the entries with one of
+    /// `keys`, e.g. `uid`, to numbers instead of strings. Converting back
+    /// with the same mapping writes the numbers of these keys in
+    /// hexadecimal again, numbers of other keys stay decimal. Only values
+    /// written the same way, i.e. lowercase digits without leading zeros,
+    /// are mapped, others like `0x0006` stay strings to keep the mapping
+    /// reversible.
+    pub fn hex_keys<I, S>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.hex_keys.extend(keys.into_iter().map(Into::into));
+        self
+    }
+}
+
+// Serialize the cs2 document `input` into `serializer`.
+pub(crate) fn serialize<S>(input: &str, mapping: &Mapping, serializer: S) -> Result<S::Ok>
+where
+    S: Serializer,
+{
+    let tree = Tree::parse(input)?;
+    let result = match tree.header {
+        Some(header) => serializer.serialize_map(Some(1)).and_then(|mut map| {
+            let key = format!("[{}]", header);
+            map.serialize_entry(&key, &Block { entries: &tree.entries, mapping })?;
+            map.end()
+        }),
+        None => Block { entries: &tree.entries, mapping }.serialize(serializer),
+    };
+    result.map_err(|e| Error::Message(e.to_string()))
+}
+
+// Transcode a document from `deserializer` into cs2 text.
+pub(crate) fn transcode<'de, D>(deserializer: D, mapping: &Mapping) -> Result<String>
+where
+    D: Deserializer<'de>,
+{
+    let mut serializer = crate::ser::Serializer::with_hex_keys(mapping.hex_keys.clone());
+    serde_transcode::transcode(deserializer, &mut serializer)
+        .map_err(|e| Error::Message(e.to_string()))?;
+    Ok(serializer.finish())
+}
+
+// Serialize a JSON or YAML value into cs2 text.
+pub(crate) fn to_string<T>(value: &T, mapping: &Mapping) -> Result<String>
+where
+    T: Serialize,
+{
+    let mut serializer = crate::ser::Serializer::with_hex_keys(mapping.hex_keys.clone());
+    value
+        .serialize(&mut serializer)
+        .map_err(|e| Error::Message(e.to_string()))?;
+    Ok(serializer.finish())
+}
+
+struct Block<'a, 'de> {
+    entries: &'a [Entry<'de>],
+    mapping: &'a Mapping,
+}
+
+impl Serialize for Block<'_, '_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut keys: Vec<&str> = vec![];
+        for entry in self.entries {
+            if !keys.contains(&entry.key) {
+                keys.push(entry.key);
+            }
+        }
+        let mut map = serializer.serialize_map(Some(keys.len()))?;
+        for key in keys {
+            let group = Group {
+                key,
+                entries: self.entries,
+                mapping: self.mapping,
+            };
+            map.serialize_entry(key, &group)?;
+        }
+        map.end()
+    }
+}
+
+// All entries of a block with the same key, an array if there are several.
+struct Group<'a, 'de> {
+    key: &'a str,
+    entries: &'a [Entry<'de>],
+    mapping: &'a Mapping,
+}
+
+impl Serialize for Group<'_, '_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut entries = self.entries.iter().filter(|entry| entry.key == self.key);
+        let count = entries.clone().count();
+        if count == 1 {
+            return self.node(&entries.next().unwrap().node, serializer);
+        }
+        let mut seq = serializer.serialize_seq(Some(count))?;
+        for entry in entries {
+            seq.serialize_element(&Element { group: self, node: &entry.node })?;
+        }
+        seq.end()
+    }
+}
+
+struct Element<'a, 'de> {
+    group: &'a Group<'a, 'de>,
+    node: &'a Node<'de>,
+}
+
+impl Serialize for Element<'_, '_> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.group.node(self.node, serializer)
+    }
+}
+
+impl Group<'_, '_> {
+    fn node<S>(&self, node: &Node, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match node {
+            Node::Block(entries) => Block {
+                entries,
+                mapping: self.mapping,
+            }
+            .serialize(serializer),
+            Node::Scalar(value) => match self.mapping.hex_number(self.key, value) {
+                Some(number) => serializer.serialize_u64(number),
+                None => serializer.serialize_str(value),
+            },
+        }
+    }
+}
+
+impl Mapping {
+    fn hex_number(&self, key: &str, value: &str) -> Option<u64> {
+        if !self.hex_keys.iter().any(|hex_key| hex_key == key) {
+            return None;
+        }
+        let digits = value.strip_prefix("0x")?;
+        let canonical = digits == "0" || !digits.starts_with('0');
+        if digits.is_empty()
+            || !canonical
+            || !digits.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+        {
+            return None;
+        }
+        u64::from_str_radix(digits, 16).ok()
+    }
+}
@@ -2,49 +2,766 @@ use std::ops::{AddAssign, MulAssign, Neg};
 
 use serde::Deserialize;
 use serde::de::{
-    self, DeserializeSeed, MapAccess, SeqAccess, Visitor,
+    self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
 };
+use serde::de::value::{BorrowedStrDeserializer, StringDeserializer};
 
 use super::error::{Error, Result};
+use super::file::{self, HeaderDeserializer};
+use super::parse::{Entry, Node, Tree};
 
 #[derive(Debug)]
 pub struct Deserializer<'de> {
-    // This string starts with the input data and characters are truncated off
-    // the beginning as data is parsed.
+    // The complete input. It is split into a tree of entries as soon as the
+    // deserialization starts, all keys and values borrow from this string.
     input: &'de str,
-    parse_value: bool,
-    keys: Vec<&'de str>,
-    names: Vec<&'de str>,
 }
 
-impl<'de> Deserializer<'de> {
-    // By convention, `Deserializer` constructors are named like `from_xyz`.
-    // That way basic use cases are satisfied by something like
-    // `serde_cs2::from_str(...)` while advanced use cases that require a
-    // deserializer can make one with `serde_cs2::Deserializer::from_str(...)`.
-    pub fn from_str(input: &'de str) -> Self {
-        Deserializer { input, parse_value: false, keys: vec![], names: vec![] }
+impl<'de> Deserializer<'de> {
+    // By convention, `Deserializer` constructors are named like `from_xyz`.
+    // That way basic use cases are satisfied by something like
+    // `serde_cs2::from_str(...)` while advanced use cases that require a
+    // deserializer can make one with `serde_cs2::Deserializer::from_str(...)`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &'de str) -> Self {
+        Deserializer { input }
+    }
+}
+
+// By convention, the public API of a Serde deserializer is one or more
+// `from_xyz` methods such as `from_str`, `from_bytes`, or `from_reader`
+// depending on what Rust types the deserializer is able to consume as input.
+//
+// This basic deserializer supports only `from_str`.
+pub fn from_str<'a, T>(s: &'a str) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_str(s);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+// Scalars are everything behind the '=' of a key value line. They are
+// deserialized by a small deserializer that only sees the value itself.
+// Primitive types requested from an entry, a group of entries or the
+// document root are forwarded to it.
+macro_rules! forward_to_scalar {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value>
+            where
+                V: Visitor<'de>,
+            {
+                let mut de = self.scalar()?;
+                let value = (&mut de).$method(visitor)?;
+                de.end()?;
+                Ok(value)
+            }
+        )*
+    };
+}
+pub(crate) use forward_to_scalar;
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    // Look at the input data to decide what Serde data model type to
+    // deserialize as. Not all data formats are able to support this operation.
+    // Formats that support `deserialize_any` are known as self-describing.
+    //
+    // A document is a map of all entries in their original order. A file
+    // header is represented as the only key of the map in brackets, e.g.
+    // `[lokomotive]`, with the entries of the file as value.
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let tree = Tree::parse(self.input)?;
+        match tree.header {
+            Some(header) => {
+                let body = Entry {
+                    key: header,
+                    node: Node::Block(tree.entries),
+                };
+                visitor.visit_map(HeaderAccess { body: Some(&body) })
+            }
+            None => visitor.visit_map(EntryAccess::new(&tree.entries)),
+        }
+    }
+
+    // A document consisting of a single value, e.g. `5`, is deserialized
+    // directly.
+    forward_to_scalar! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_identifier
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // A sequence on the top level are all blocks of the document, e.g. a
+    // list of `lokomotive` entries without a file header.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let tree = self.tree()?;
+        visitor.visit_seq(EntrySeq::new(tree.entries.iter(), true))
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut de = self.scalar()?;
+        let value = (&mut de).deserialize_tuple(len, visitor)?;
+        de.end()?;
+        Ok(value)
+    }
+
+    // Tuple structs look just like tuples.
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    // A map on the top level contains all entries of the document.
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let tree = self.tree()?;
+        visitor.visit_map(BlockAccess::new(&tree.entries))
+    }
+
+    // Structs start with the struct name in the first line.
+    // Followed by all fields in a new line and indented by one level
+    //
+    // A `File` is the only struct which starts with a file header, the
+    // entries on the top level are the body of the file.
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name == file::NAME {
+            let tree = Tree::parse(self.input)?;
+            let header = tree.header.ok_or(Error::ExpectedHeader)?;
+            let body = Entry {
+                key: header,
+                node: Node::Block(tree.entries),
+            };
+            return visitor.visit_map(FileAccess::new(&body));
+        }
+        let tree = self.tree()?;
+        match tree.entries.first() {
+            Some(entry) => {
+                EntryDeserializer::top_level(entry).deserialize_struct(name, fields, visitor)
+            }
+            None => Err(Error::Eof),
+        }
+    }
+
+    // Only unit variants are supported, written as the variant name.
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut de = self.scalar()?;
+        let value = (&mut de).deserialize_enum(name, variants, visitor)?;
+        de.end()?;
+        Ok(value)
+    }
+
+    // Like `deserialize_any` but indicates to the `Deserializer` that it makes
+    // no difference which `Visitor` method is called because the data is
+    // ignored. Since the input is already split into entries nothing has
+    // to be skipped.
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+impl<'de> Deserializer<'de> {
+    // Split the input into entries. A file header is only accepted by `File`.
+    fn tree(&self) -> Result<Tree<'de>> {
+        let tree = Tree::parse(self.input)?;
+        if tree.header.is_some() {
+            return Err(Error::UnexpectedHeader);
+        }
+        Ok(tree)
+    }
+
+    fn scalar(&self) -> Result<ValueDeserializer<'de>> {
+        Ok(ValueDeserializer::new(self.input.trim()))
+    }
+}
+
+// Deserializer for the value of a single entry. This is either a scalar or
+// a block with nested entries.
+struct EntryDeserializer<'a, 'de: 'a> {
+    entry: &'a Entry<'de>,
+    // blocks on the top level are named like the struct, nested blocks
+    // are named by the field key of the parent struct
+    top_level: bool,
+}
+
+impl<'a, 'de> EntryDeserializer<'a, 'de> {
+    fn new(entry: &'a Entry<'de>) -> Self {
+        EntryDeserializer { entry, top_level: false }
+    }
+
+    fn top_level(entry: &'a Entry<'de>) -> Self {
+        EntryDeserializer { entry, top_level: true }
+    }
+
+    fn scalar(&self) -> Result<ValueDeserializer<'de>> {
+        match self.entry.node {
+            Node::Scalar(value) => Ok(ValueDeserializer::new(value)),
+            Node::Block(_) => Err(Error::ExpectedValueSeperator),
+        }
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for EntryDeserializer<'a, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.entry.node {
+            Node::Scalar(value) => (&mut ValueDeserializer::new(value)).deserialize_any(visitor),
+            Node::Block(entries) => visitor.visit_map(EntryAccess::new(entries)),
+        }
+    }
+
+    forward_to_scalar! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_identifier
+        deserialize_seq
+    }
+
+    // An entry which is present always contains a value.
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut de = self.scalar()?;
+        let value = (&mut de).deserialize_tuple(len, visitor)?;
+        de.end()?;
+        Ok(value)
+    }
+
+    // Tuple structs look just like tuples.
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.entry.node {
+            Node::Block(entries) => visitor.visit_map(BlockAccess::new(entries)),
+            Node::Scalar(_) => Err(Error::ExpectedNewline),
+        }
+    }
+
+    // The nested entries are the fields. On the top level the key of the
+    // entry has to be the struct name.
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.top_level && self.entry.key != name {
+            return Err(Error::ExpectedStructName);
+        }
+        self.deserialize_map(visitor)
+    }
+
+    // Only unit variants are supported, written as the variant name.
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut de = self.scalar()?;
+        let value = (&mut de).deserialize_enum(name, variants, visitor)?;
+        de.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+// All entries of a block with the same key. CS2 repeats the key for every
+// element of a collection, e.g. one `.funktionen` block per function, and
+// the elements are not necessarily contiguous. A group is deserialized as
+// sequence for collections, any other type requires a single entry.
+struct GroupDeserializer<'a, 'de: 'a> {
+    entries: Vec<&'a Entry<'de>>,
+}
+
+impl<'a, 'de> GroupDeserializer<'a, 'de> {
+    fn single(&self) -> Result<EntryDeserializer<'a, 'de>> {
+        match self.entries.as_slice() {
+            [entry] => Ok(EntryDeserializer::new(entry)),
+            _ => Err(Error::DuplicateKey),
+        }
+    }
+
+    fn scalar(&self) -> Result<ValueDeserializer<'de>> {
+        self.single()?.scalar()
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for GroupDeserializer<'a, 'de> {
+    type Error = Error;
+
+    // Repeated entries without a type hint are a sequence.
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.entries.as_slice() {
+            [entry] => EntryDeserializer::new(entry).deserialize_any(visitor),
+            _ => self.deserialize_seq(visitor),
+        }
+    }
+
+    forward_to_scalar! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_identifier
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // Every entry of the group is one element of the sequence.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(EntrySeq::new(self.entries.into_iter(), false))
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_map(visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+// `SeqAccess` over a list of entries, used for repeated blocks and for
+// the entries on the top level of a document.
+struct EntrySeq<I> {
+    entries: I,
+    top_level: bool,
+}
+
+impl<I> EntrySeq<I> {
+    fn new(entries: I, top_level: bool) -> Self {
+        EntrySeq { entries, top_level }
     }
 }
 
-// By convention, the public API of a Serde deserializer is one or more
-// `from_xyz` methods such as `from_str`, `from_bytes`, or `from_reader`
-// depending on what Rust types the deserializer is able to consume as input.
-//
-// This basic deserializer supports only `from_str`.
-pub fn from_str<'a, T>(s: &'a str) -> Result<T>
+impl<'de, 'a, I> SeqAccess<'de> for EntrySeq<I>
 where
-    T: Deserialize<'a>,
+    'de: 'a,
+    I: Iterator<Item = &'a Entry<'de>>,
 {
-    let mut deserializer = Deserializer::from_str(s);
-    let t = T::deserialize(&mut deserializer)?;
-    Ok(t)
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some(entry) => {
+                let de = EntryDeserializer { entry, top_level: self.top_level };
+                seed.deserialize(de).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+// The entries of a block grouped by key. The groups keep the order in which
+// the keys appear first in the block.
+struct BlockAccess<'a, 'de: 'a> {
+    groups: std::vec::IntoIter<(&'de str, Vec<&'a Entry<'de>>)>,
+    value: Option<Vec<&'a Entry<'de>>>,
+}
+
+impl<'a, 'de> BlockAccess<'a, 'de> {
+    fn new(entries: &'a [Entry<'de>]) -> Self {
+        let mut groups: Vec<(&'de str, Vec<&'a Entry<'de>>)> = vec![];
+        for entry in entries {
+            match groups.iter_mut().find(|(key, _)| *key == entry.key) {
+                Some((_, group)) => group.push(entry),
+                None => groups.push((entry.key, vec![entry])),
+            }
+        }
+        BlockAccess {
+            groups: groups.into_iter(),
+            value: None,
+        }
+    }
+}
+
+// `MapAccess` is provided to the `Visitor` to give it the ability to iterate
+// through entries of the map.
+impl<'de, 'a> MapAccess<'de> for BlockAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.groups.next() {
+            Some((key, entries)) => {
+                self.value = Some(entries);
+                seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(entries) => seed.deserialize(GroupDeserializer { entries }),
+            None => Err(Error::ExpectedValueSeperator),
+        }
+    }
+}
+
+// The entries of a block in their original order including repeated keys.
+// Used when the type is not known in advance, e.g. by `Value`.
+struct EntryAccess<'a, 'de: 'a> {
+    entries: std::slice::Iter<'a, Entry<'de>>,
+    value: Option<&'a Entry<'de>>,
+}
+
+impl<'a, 'de> EntryAccess<'a, 'de> {
+    fn new(entries: &'a [Entry<'de>]) -> Self {
+        EntryAccess {
+            entries: entries.iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de, 'a> MapAccess<'de> for EntryAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some(entry) => {
+                self.value = Some(entry);
+                seed.deserialize(BorrowedStrDeserializer::new(entry.key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(entry) => seed.deserialize(EntryDeserializer::new(entry)),
+            None => Err(Error::ExpectedValueSeperator),
+        }
+    }
+}
+
+// A document with file header seen as map with the header in brackets as
+// only key.
+struct HeaderAccess<'a, 'de: 'a> {
+    body: Option<&'a Entry<'de>>,
+}
+
+impl<'de, 'a> MapAccess<'de> for HeaderAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.body {
+            Some(body) => {
+                let key = format!("[{}]", body.key);
+                seed.deserialize(StringDeserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.body.take() {
+            Some(body) => seed.deserialize(EntryDeserializer::new(body)),
+            None => Err(Error::ExpectedValueSeperator),
+        }
+    }
+}
+
+// `MapAccess` for a `File`, the header is provided as first field followed
+// by the body with all entries on the top level.
+struct FileAccess<'a, 'de: 'a> {
+    body: &'a Entry<'de>,
+    field: usize,
+}
+
+impl<'a, 'de> FileAccess<'a, 'de> {
+    fn new(body: &'a Entry<'de>) -> Self {
+        FileAccess { body, field: 0 }
+    }
+}
+
+impl<'de, 'a> MapAccess<'de> for FileAccess<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let key = match self.field {
+            0 => file::HEADER,
+            1 => file::BODY,
+            _ => return Ok(None),
+        };
+        seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.field += 1;
+        match self.field {
+            1 => seed.deserialize(HeaderDeserializer::new(self.body.key)),
+            _ => seed.deserialize(EntryDeserializer::new(self.body)),
+        }
+    }
+}
+
+// Deserializer for a single scalar value, i.e. the text behind the '='.
+pub(crate) struct ValueDeserializer<'de> {
+    // This string starts with the value and characters are truncated off
+    // the beginning as data is parsed.
+    input: &'de str,
 }
 
 // SERDE IS NOT A PARSING LIBRARY. This impl block defines a few basic parsing
 // functions from scratch. More complicated formats may wish to use a dedicated
 // parsing library to help implement their Serde deserializer.
-impl<'de> Deserializer<'de> {
+impl<'de> ValueDeserializer<'de> {
+    pub(crate) fn new(input: &'de str) -> Self {
+        ValueDeserializer { input }
+    }
+
+    // The whole value must be consumed by the deserialized type.
+    pub(crate) fn end(&self) -> Result<()> {
+        if self.input.trim_end().is_empty() {
+            Ok(())
+        } else {
+            Err(Error::TrailingCharacters)
+        }
+    }
+
     // Look at the first character in the input without consuming it.
     fn peek_char(&mut self) -> Result<char> {
         self.input.chars().next().ok_or(Error::Eof)
@@ -57,14 +774,12 @@ impl<'de> Deserializer<'de> {
         Ok(ch)
     }
 
-    // Parse the identifier `true` or `false`.
+    // Parse the identifier `1` or `0`.
     fn parse_bool(&mut self) -> Result<bool> {
-        if self.next_char()? == '1' {
-            Ok(true)
-        } else if self.next_char()? == '0' {
-            Ok(false)
-        } else {
-            Err(Error::ExpectedBoolean)
+        match self.next_char()? {
+            '1' => Ok(true),
+            '0' => Ok(false),
+            _ => Err(Error::ExpectedBoolean),
         }
     }
 
@@ -104,18 +819,12 @@ impl<'de> Deserializer<'de> {
         if self.input.starts_with("0x") {
             self.input = &self.input[2..];
         }
-        if let Some(len) = self.input.find('\n') {
-            let bytes = self.input[..len].as_bytes();
-            self.input = &self.input[len..];
-            return Ok(bytes);
-        }
-        let len = self.input.len();
-        if len > 0 {
-            let bytes = self.input[..len].as_bytes();
-            self.input = &self.input[len..];
-            return Ok(bytes);
+        if self.input.is_empty() {
+            return Err(Error::ExpectedNewline);
         }
-        return Err(Error::ExpectedNewline);
+        let bytes = self.input.as_bytes();
+        self.input = "";
+        Ok(bytes)
     }
 
     // Parse a possible minus sign followed by a group of decimal digits as a
@@ -152,60 +861,37 @@ impl<'de> Deserializer<'de> {
         }
     }
 
-    // Parse a string until the next newline or '=' character.
-    //
+    // A float is the remaining value in any notation `f64::from_str` accepts.
+    fn parse_float<T: std::str::FromStr>(&mut self) -> Result<T> {
+        self.parse_string()?.trim_end().parse().map_err(|_| Error::ExpectedFloat)
+    }
+
+    // A string is the remaining value up to the end of the line.
     fn parse_string(&mut self) -> Result<&'de str> {
-        if self.input.starts_with('.') {
-            // this is a key values since it started with an .
-            self.input = self.input.trim_start_matches('.');
-            let line = self.input.lines().next().unwrap();
-            if let Some(len) = line.find('=') {
-                let s = &self.input[..len];
-                self.input = &self.input[len..];
-                self.parse_value = true;
-                Ok(s)
-            } else {
-                // this must be a sequence start, we just return the key value
-                // without moving the string forward since the key is also the
-                // name of the struct inside the sequence
-                Ok(line)
-            }
-        } else if let Some(len) = self.input.find('\n') {
-            let s = &self.input[..len];
-            // if this is a value we remove the value from the input and return the value
-            // if it's not a value it is a struct name at the front of a line
-            // e.g. if we deserialize a full file including the file header [...]
-            // in this case we don't remove the name since we still need the struct name
-            if self.parse_value {
-                self.input = &self.input[len..];
-            }
-            Ok(s)
-        } else if self.input.len() > 0 {
-            let len = self.input.len();
-            let s = &self.input[..len];
-            self.input = &self.input[len..];
-            Ok(s)
-        } else {
-            Err(Error::ExpectedString)
-        }
+        let s = self.input;
+        self.input = "";
+        Ok(s)
     }
 }
 
-impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
+impl<'de> de::Deserializer<'de> for &mut ValueDeserializer<'de> {
     type Error = Error;
 
     // Look at the input data to decide what Serde data model type to
     // deserialize as. Not all data formats are able to support this operation.
     // Formats that support `deserialize_any` are known as self-describing.
-    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    //
+    // Without a type hint a value can't be distinguished from an array or a
+    // number, it's always handed out as string.
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!("deserialize_any() {:?}", self.peek_char());
+        self.deserialize_str(visitor)
     }
 
-    // Uses the `parse_bool` parsing function defined above to read the 
-    // identifier `true` or `false` from the input.
+    // Uses the `parse_bool` parsing function defined above to read the
+    // identifier `1` or `0` from the input.
     //
     // Parsing refers to looking at the input and deciding that it contains the
     // value `true` or `false`.
@@ -283,30 +969,32 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         visitor.visit_u64(self.parse_unsigned()?)
     }
 
-    // Float parsing is stupidly hard.
-    fn deserialize_f32<V>(self, _visitor: V) -> Result<V::Value>
+    // Float parsing is stupidly hard, it's left to the standard library.
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!()
+        visitor.visit_f32(self.parse_float()?)
     }
 
-    // Float parsing is stupidly hard.
-    fn deserialize_f64<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!()
+        visitor.visit_f64(self.parse_float()?)
     }
 
     // The `Serializer` implementation on the previous page serialized chars as
     // single-character strings so handle that representation here.
-    fn deserialize_char<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        // Parse a string, check that it is one character, call `visit_char`.
-        unimplemented!()
+        let mut chars = self.parse_string()?.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => visitor.visit_char(ch),
+            _ => Err(Error::ExpectedChar),
+        }
     }
 
     // Refer to the "Understanding deserializer lifetimes" page for information
@@ -334,11 +1022,11 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         visitor.visit_borrowed_bytes(self.parse_bytes()?)
     }
 
-    fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!()
+        self.deserialize_bytes(visitor)
     }
 
     // As commented in `Serializer` implementation, this is a lossy
@@ -351,25 +1039,27 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     }
 
     // In Serde, unit means an anonymous value containing no data.
-    // Units are not used in cs2
-    fn deserialize_unit<V>(self, _visitor: V) -> Result<V::Value>
+    // Units are not used in cs2, an empty value is accepted as unit.
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!()
+        match self.parse_string()? {
+            "" => visitor.visit_unit(),
+            _ => Err(Error::TrailingCharacters),
+        }
     }
 
     // Unit struct means a named value containing no data.
-    // Unit structs are not used in cs2
     fn deserialize_unit_struct<V>(
         self,
         _name: &'static str,
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!()
+        self.deserialize_unit(visitor)
     }
 
     // As is done here, serializers are encouraged to treat newtype structs as
@@ -386,20 +1076,13 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         visitor.visit_newtype_struct(self)
     }
 
-    // Deserialization of compound types like sequences and maps happens by
-    // passing the visitor an "Access" object that gives it the ability to
-    // iterate through the data contained in the sequence.
+    // A sequence inside a single value is an array.
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        // Give the visitor access to each element of the sequence.
-        let value = visitor.visit_seq(NewlineSeparated::new(self))?;
-        self.names.pop();
-        if self.names.last().is_some_and(|name| self.keys.last().is_some_and(|key| key != name)) {
-            self.keys.pop();
-        }
-        Ok(value)
+        self.input = self.input.trim_start();
+        visitor.visit_seq(SpaceSeparated::new(self))
     }
 
     // Tuples look just like arrays in cs2.
@@ -411,90 +1094,55 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
     where
         V: Visitor<'de>,
     {
-        // this is an array in the cs2 data
-        // Give the visitor access to each element of the sequence.
-        self.input = &self.input.trim_start();
-        let value = visitor.visit_seq(SpaceSeparated::new(self))?;
-        Ok(value)
+        self.deserialize_seq(visitor)
     }
 
-    // Tuple structs are not used in cs2.
+    // Tuple structs look just like arrays.
     fn deserialize_tuple_struct<V>(
         self,
         _name: &'static str,
         _len: usize,
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!()
+        self.deserialize_seq(visitor)
     }
 
-    // Much like `deserialize_seq` but calls the visitors `visit_map` method
-    // with a `MapAccess` implementation, rather than the visitor's `visit_seq`
-    // method with a `SeqAccess` implementation.
-    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    // A single value never contains a map.
+    fn deserialize_map<V>(self, _visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        let value = visitor.visit_map(NewlineSeparated::new(self))?;
-        self.names.pop();
-        if self.names.is_empty() {
-            self.keys.pop();
-        } else if self.names.last().is_some_and(|name| self.keys.last().is_some_and(|key| key != name)) {
-            self.keys.pop();
-        }
-        return Ok(value);
+        Err(Error::ExpectedNewline)
     }
 
-    // Structs start with the struct name in the first line.
-    // Followed by all fields in a new line and indented by one level
-    //
-    // Notice the `fields` parameter - a "struct" in the Serde data model means
-    // that the `Deserialize` implementation is required to know what the fields
-    // are before even looking at the input data. Any key-value pairing in which
-    // the fields cannot be known ahead of time is probably a map.
+    // A single value never contains a struct.
     fn deserialize_struct<V>(
         self,
-        name: &'static str,
+        _name: &'static str,
         _fields: &'static [&'static str],
-        visitor: V,
+        _visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        // skip new lines in front
-        if self.peek_char() == Ok('\n') {
-            let _ = self.next_char();
-        }
-        if !self.input.trim_start().starts_with(name) {
-            return Err(Error::ExpectedStructName);
-        }
-        if let Some(len) = self.input.find('\n') {
-            self.input = &self.input[len + 1..];
-            // we deserialize the full structure map
-            self.names.push(name);
-            if !self.keys.last().is_some_and(|key| *key == name) {
-                if !(self.keys.is_empty() && name.starts_with("[") && name.ends_with("]")) {
-                    self.keys.push(name);
-                }
-            }
-            return self.deserialize_map(visitor);
-        }
         Err(Error::ExpectedNewline)
     }
 
+    // A unit variant is the name of the variant, there is no representation
+    // for variants with data.
     fn deserialize_enum<V>(
         self,
         _name: &'static str,
         _variants: &'static [&'static str],
-        _visitor: V,
+        visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        unimplemented!()
+        visitor.visit_enum(self.parse_string()?.into_deserializer())
     }
 
     // An identifier in Serde is the type that identifies a field of a struct or
@@ -506,35 +1154,25 @@ impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
         self.deserialize_str(visitor)
     }
 
-    // Like `deserialize_any` but indicates to the `Deserializer` that it makes
-    // no difference which `Visitor` method is called because the data is
-    // ignored.
-    //
-    // Some deserializers are able to implement this more efficiently than
-    // `deserialize_any`, for example by rapidly skipping over matched
-    // delimiters without paying close attention to the data in between.
-    //
-    // Some formats are not able to implement this at all. Formats that can
-    // implement `deserialize_any` and `deserialize_ignored_any` are known as
-    // self-describing.
     fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_any(visitor)
+        self.input = "";
+        visitor.visit_unit()
     }
 }
 
-// In order to handle arrays correctly when deserializing a cs2 struct
+// In order to handle arrays correctly when deserializing a cs2 value
 // we need to track whether we are on the first element or past the first
 // element.
 struct SpaceSeparated<'a, 'de: 'a> {
-    de: &'a mut Deserializer<'de>,
+    de: &'a mut ValueDeserializer<'de>,
     first: bool,
 }
 
 impl<'a, 'de> SpaceSeparated<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>) -> Self {
+    fn new(de: &'a mut ValueDeserializer<'de>) -> Self {
         SpaceSeparated {
             de,
             first: true,
@@ -551,11 +1189,8 @@ impl<'de, 'a> SeqAccess<'de> for SpaceSeparated<'a, 'de> {
     where
         T: DeserializeSeed<'de>,
     {
-        if self.de.input.is_empty() {
-            return Ok(None);
-        }
         // Check if there are no more elements.
-        if self.de.peek_char()? == '\n' {
+        if self.de.input.trim_end().is_empty() {
             return Ok(None);
         }
         // Space is required before every element except the first.
@@ -563,129 +1198,14 @@ impl<'de, 'a> SeqAccess<'de> for SpaceSeparated<'a, 'de> {
             return Err(Error::ExpectedArraySeperator);
         }
         self.first = false;
-        // Deserialize an array element.
-        seed.deserialize(&mut *self.de).map(Some)
-    }
-}
-
-// In order to handle struct collections correctly when deserializing a cs2 struct
-// we need to track whether we are on the first element or past the first
-// element.
-struct NewlineSeparated<'a, 'de: 'a> {
-    de: &'a mut Deserializer<'de>,
-    first: bool,
-}
-
-impl<'a, 'de> NewlineSeparated<'a, 'de> {
-    fn new(de: &'a mut Deserializer<'de>) -> Self {
-        NewlineSeparated {
-            de,
-            first: true,
-        }
-    }
-}
-
-// `SeqAccess` is provided to the `Visitor` to give it the ability to iterate
-// through elements of the sequence.
-impl<'de, 'a> SeqAccess<'de> for NewlineSeparated<'a, 'de> {
-    type Error = Error;
-
-    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
-    where
-        T: DeserializeSeed<'de>,
-    {
-        if self.de.input.is_empty() {
-            return Ok(None);
-        }
-        let str = self.de.input.trim_start_matches('\n').trim_start();
-
-        // Check if there are no more elements.
-        let level = str.chars().take_while(|c| *c == '.').count();
-        if !self.first && level >= self.de.keys.len() {
-            return Err(Error::WrongLevel);
-        }
-
-        let str = str.trim_start_matches('.');
-        // line is not the expected key for the current sequence so we are at the end of
-        // this sequence
-        let key = str.split_whitespace().next().unwrap_or("").split('=').next().unwrap_or("");
-        if !self.first && key != *self.de.keys.last().unwrap() {
-            return Ok(None);
-        }
-        // remove leading ' ' from the value string
-        self.de.input = str.trim_start();
-        if self.first {
-            // this is the start of a sequence, push name to keys
-            self.de.names.push(key);
-        }
-        self.first = false;
-        // Deserialize an array element.
-        seed.deserialize(&mut *self.de).map(Some)
-    }
-}
-
-// `MapAccess` is provided to the `Visitor` to give it the ability to iterate
-// through entries of the map.
-impl<'de, 'a> MapAccess<'de> for NewlineSeparated<'a, 'de> {
-    type Error = Error;
-
-    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
-    where
-        K: DeserializeSeed<'de>,
-    {
-        // Check if there are no more entries.
-        if self.de.peek_char() == Err(Error::Eof) {
-            return Ok(None);
-        }
-
-        let str = self.de.input.trim_start_matches('\n').trim_start();
-        if str.is_empty() {
-            self.de.input = str;
-            return Ok(None);
-        }
-
-        // Check if we are in the correct level
-        let level = str.chars().take_while(|c| *c == '.').count();
-        if level < self.de.keys.len() {
-            return Ok(None)
-        }
-
-        // Newline is required before every entry except the first.
-        if !self.first && self.de.next_char()? != '\n' {
-            return Err(Error::ExpectedNewline);
-        }
-        self.first = false;
-
-        // skip white spaces
-        self.de.input = self.de.input.trim_start();
-
-        //check for empty strings
-        if self.de.input.is_empty() {
-            return Ok(None);
-        }
-
-        // Deserialize a map key.
-        self.de.parse_value = false;
-        seed.deserialize(&mut *self.de).map(Some)
-    }
-
-    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
-    where
-        V: DeserializeSeed<'de>,
-    {
-        // It doesn't make a difference whether the colon is parsed at the end
-        // of `next_key_seed` or at the beginning of `next_value_seed`. In this
-        // case the code is a bit simpler having it here.
-        // value is separated by a '='
-        if self.de.peek_char() != Ok('=') {
-            // TODO: check if this is a array start
-            // we can do this by keeping the. In front of the struct name?
-            return seed.deserialize(&mut *self.de);
-        }
-        if self.de.next_char()? != '=' {
-            return Err(Error::ExpectedValueSeperator);
-        }
-        // Deserialize a map value.
-        seed.deserialize(&mut *self.de)
+        // Deserialize an array element. It ends at the next blank, so
+        // strings don't swallow the following elements.
+        let input = self.de.input;
+        let (element, rest) = input.split_at(input.find(' ').unwrap_or(input.len()));
+        self.de.input = rest;
+        let mut de = ValueDeserializer::new(element);
+        let value = seed.deserialize(&mut de)?;
+        de.end()?;
+        Ok(Some(value))
     }
 }
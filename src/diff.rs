@@ -0,0 +1,355 @@
+// Structural comparison of two documents.
+//
+// Entries are compared key by key. Repeated blocks like the `lokomotive`s of
+// a file or the `funktionen` of a lokomotive are matched by their identity,
+// the value of the first identity key found in the block, so reordering
+// blocks is not a change. Blocks without identity are matched by position.
+//
+// Every change is reported with a path in the syntax of `select`, e.g.
+//
+// lokomotive[name="BR 218"].funktionen[nr=3].typ
+
+use std::fmt::{self, Display};
+
+use serde::ser::{Serialize, SerializeStruct};
+
+use crate::value::Value;
+
+/// The keys identifying a block among repeated blocks with the same key,
+/// tried in their order. The default is `uid`, `name` and `nr`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Identity {
+    keys: Vec<String>,
+}
+
+impl Default for Identity {
+    fn default() -> Self {
+        Identity::new(["uid", "name", "nr"])
+    }
+}
+
+impl Identity {
+    pub fn new<I, S>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Identity {
+            keys: keys.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// The identity key and its value of a block, `None` if the block has
+    /// none of the identity keys.
+    pub fn of<'a>(&'a self, block: &'a Value) -> Option<(&'a str, &'a str)> {
+        self.keys
+            .iter()
+            .find_map(|key| Some((key.as_str(), block.get(key)?.as_str()?)))
+    }
+}
+
+/// All changes between two documents, see `diff`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+/// A single added, removed or changed entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub path: String,
+    pub kind: ChangeKind,
+    /// The value in the old document, `None` for added entries.
+    pub old: Option<Value>,
+    /// The value in the new document, `None` for removed entries.
+    pub new: Option<Value>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// Compare two documents with the default `Identity`.
+///
+/// The `Display` output lists one change per line, `Diff` serializes into
+/// a list of `change` blocks for further processing.
+///
+/// ```
+/// let old = serde_cs2::from_str("lokomotive\n .name=BR 218\n .adresse=0x6\n").unwrap();
+/// let new = serde_cs2::from_str("lokomotive\n .name=BR 218\n .adresse=0x7\n").unwrap();
+/// let diff = serde_cs2::diff(&old, &new);
+/// assert_eq!(diff.to_string(), "~ lokomotive[name=\"BR 218\"].adresse: 0x6 -> 0x7\n");
+/// ```
+pub fn diff(old: &Value, new: &Value) -> Diff {
+    diff_with(old, new, &Identity::default())
+}
+
+/// Compare two documents, matching repeated blocks by `identity`.
+pub fn diff_with(old: &Value, new: &Value, identity: &Identity) -> Diff {
+    let mut diff = Diff::default();
+    let (old, new) = match (header(old), header(new)) {
+        (Some((old_header, old)), Some((new_header, new))) if old_header == new_header => (old, new),
+        _ => (old, new),
+    };
+    diff.block(&mut String::new(), old, new, identity);
+    diff
+}
+
+// The body of a file with header, which is the only entry of the document.
+pub(crate) fn header(value: &Value) -> Option<(&str, &Value)> {
+    match value.as_block()? {
+        [(key, body)] if key.starts_with('[') && key.ends_with(']') => Some((key, body)),
+        _ => None,
+    }
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Change> {
+        self.changes.iter()
+    }
+
+    fn push(&mut self, path: &str, kind: ChangeKind, old: Option<&Value>, new: Option<&Value>) {
+        self.changes.push(Change {
+            path: path.to_owned(),
+            kind,
+            old: old.cloned(),
+            new: new.cloned(),
+        });
+    }
+
+    // Compare two blocks. Keys are visited in the order of the old block,
+    // followed by the keys which are new.
+    fn block(&mut self, path: &mut String, old: &Value, new: &Value, identity: &Identity) {
+        for key in keys(&[old, new]) {
+            let old_values: Vec<&Value> = old.get_all(key).collect();
+            let new_values: Vec<&Value> = new.get_all(key).collect();
+            let len = path.len();
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(key);
+            self.entries(path, &old_values, &new_values, identity);
+            path.truncate(len);
+        }
+    }
+
+    // Compare all values of a key.
+    fn entries(&mut self, path: &mut String, old: &[&Value], new: &[&Value], identity: &Identity) {
+        let is_block = |value: &&Value| matches!(value, Value::Block(_));
+        if !old.iter().all(is_block) || !new.iter().all(is_block) {
+            match (old, new) {
+                ([], []) => {}
+                ([], [new]) => self.push(path, ChangeKind::Added, None, Some(new)),
+                ([old], []) => self.push(path, ChangeKind::Removed, Some(old), None),
+                ([old], [new]) if old == new => {}
+                ([old], [new]) => self.push(path, ChangeKind::Changed, Some(old), Some(new)),
+                _ => {
+                    let old = list(old);
+                    let new = list(new);
+                    if old != new {
+                        self.push(path, ChangeKind::Changed, Some(&old), Some(&new));
+                    }
+                }
+            }
+            return;
+        }
+
+        for (blocks, filter) in match_blocks(&[old, new], identity) {
+            let len = path.len();
+            path.push_str(&filter);
+            match blocks[..] {
+                [Some(old), Some(new)] => self.block(path, old, new, identity),
+                [Some(old), None] => self.push(path, ChangeKind::Removed, Some(old), None),
+                [None, Some(new)] => self.push(path, ChangeKind::Added, None, Some(new)),
+                _ => {}
+            }
+            path.truncate(len);
+        }
+    }
+}
+
+// The keys of several blocks in the order of their first appearance, each
+// key once.
+pub(crate) fn keys<'a>(blocks: &[&'a Value]) -> Vec<&'a str> {
+    let mut keys: Vec<&str> = vec![];
+    for block in blocks {
+        for (key, _) in block.as_block().unwrap_or_default() {
+            if !keys.contains(&key.as_str()) {
+                keys.push(key);
+            }
+        }
+    }
+    keys
+}
+
+fn list(values: &[&Value]) -> Value {
+    Value::List(values.iter().map(|value| (*value).clone()).collect())
+}
+
+// The matching blocks of a key in several documents, a block might be
+// missing in some of them, with the filter selecting them in a path.
+pub(crate) type Matched<'a> = (Vec<Option<&'a Value>>, String);
+
+// Match the repeated blocks of a key by identity, blocks without identity
+// by their position. A single block without identity needs no filter in
+// the path.
+pub(crate) fn match_blocks<'a>(lists: &[&[&'a Value]], identity: &Identity) -> Vec<Matched<'a>> {
+    if lists.iter().all(|list| list.len() <= 1) {
+        let mut ids = lists.iter().filter_map(|list| list.first()).map(|block| identity.of(block));
+        let first = ids.next().flatten();
+        if ids.all(|id| id == first) {
+            let blocks = lists.iter().map(|list| list.first().copied()).collect();
+            let filter = first.map(|(key, value)| selector(key, value)).unwrap_or_default();
+            return vec![(blocks, filter)];
+        }
+    }
+
+    let mut unmatched: Vec<Vec<Option<&Value>>> = lists
+        .iter()
+        .map(|list| list.iter().copied().map(Some).collect())
+        .collect();
+    let mut matched = vec![];
+    for list in 0..lists.len() {
+        for index in 0..unmatched[list].len() {
+            let Some(block) = unmatched[list][index].take() else {
+                continue;
+            };
+            let id = identity.of(block);
+            let blocks = (0..lists.len())
+                .map(|other| match other {
+                    _ if other == list => Some(block),
+                    _ if other < list => None,
+                    _ => {
+                        let found = unmatched[other]
+                            .iter()
+                            .position(|new| new.is_some_and(|new| identity.of(new) == id));
+                        found.and_then(|index| unmatched[other][index].take())
+                    }
+                })
+                .collect();
+            matched.push((blocks, filter(id, index)));
+        }
+    }
+    matched
+}
+
+// The filter selecting a block in a path, the index for blocks without
+// identity.
+fn filter(id: Option<(&str, &str)>, index: usize) -> String {
+    match id {
+        Some((key, value)) => selector(key, value),
+        None => format!("[{}]", index),
+    }
+}
+
+// A filter like `[nr=3]`, values with other characters than letters and
+// digits are quoted.
+pub(crate) fn selector(key: &str, value: &str) -> String {
+    if value.chars().all(|c| c.is_ascii_alphanumeric()) && !value.is_empty() {
+        format!("[{}={}]", key, value)
+    } else {
+        format!("[{}=\"{}\"]", key, value)
+    }
+}
+
+impl<'a> IntoIterator for &'a Diff {
+    type Item = &'a Change;
+    type IntoIter = std::slice::Iter<'a, Change>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.kind, &self.old, &self.new) {
+            (ChangeKind::Added, _, Some(new)) => {
+                write!(f, "+ {}", self.path)?;
+                write_value(f, "=", new)
+            }
+            (ChangeKind::Removed, Some(old), _) => {
+                write!(f, "- {}", self.path)?;
+                write_value(f, "=", old)
+            }
+            (_, old, new) => {
+                write!(f, "~ {}", self.path)?;
+                if let Some(old) = old {
+                    write_value(f, ": ", old)?;
+                }
+                if let Some(new) = new {
+                    write_value(f, " -> ", new)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Scalars are shown behind the path, blocks are too long for a single line.
+fn write_value(f: &mut fmt::Formatter, separator: &str, value: &Value) -> fmt::Result {
+    match value {
+        Value::String(s) => write!(f, "{}{}", separator, s),
+        Value::Array(values) => write!(f, "{}{}", separator, values.join(" ")),
+        Value::List(values) if values.iter().all(|value| value.as_str().is_some()) => {
+            let values: Vec<_> = values.iter().filter_map(Value::as_str).collect();
+            write!(f, "{}[{}]", separator, values.join(", "))
+        }
+        _ if separator == "=" => Ok(()),
+        _ => write!(f, "{}{{...}}", separator),
+    }
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Changed => "changed",
+        }
+    }
+}
+
+// A diff is a list of `change` blocks.
+impl Serialize for Diff {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.changes.serialize(serializer)
+    }
+}
+
+impl Serialize for Change {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut change = serializer.serialize_struct("change", 4)?;
+        change.serialize_field("path", &self.path)?;
+        change.serialize_field("kind", self.kind.as_str())?;
+        change.serialize_field("old", &self.old)?;
+        change.serialize_field("new", &self.new)?;
+        change.end()
+    }
+}
@@ -0,0 +1,495 @@
+//! Format preserving editing of cs2 files.
+//!
+//! A `Document` keeps every line of the input as it was read, including
+//! indentation, blank lines and line endings. Only entries which are changed
+//! or inserted are written again, everything else is copied byte by byte.
+//!
+//! ```
+//! use serde_cs2::Document;
+//!
+//! let mut doc: Document = "[lokomotive]\r\nlokomotive\r\n .name=Lok\r\n  .adresse=0x5\r\n"
+//!     .parse()
+//!     .unwrap();
+//! let lok = doc.get_mut("lokomotive").unwrap().block_mut().unwrap();
+//! lok.set("adresse", "0x6");
+//! lok.set("vmax", "120");
+//! assert_eq!(
+//!     doc.to_string(),
+//!     "[lokomotive]\r\nlokomotive\r\n .name=Lok\r\n  .adresse=0x6\r\n .vmax=120\r\n"
+//! );
+//! ```
+
+use std::fmt::{self, Display};
+use std::iter::Peekable;
+use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::parse::Line;
+
+/// A cs2 file for editing in place, see the module documentation.
+///
+/// The entries on the top level are accessible through `Deref` to `Block`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Document {
+    // Blank lines in front of the header and the header line itself
+    prologue: String,
+    header: Option<String>,
+    root: Block,
+    // Blank lines behind the last entry
+    epilogue: Vec<String>,
+    // Line ending for inserted lines, taken from the first line
+    newline: &'static str,
+}
+
+/// The entries of a block or of the top level of a document in their order.
+/// Keys might repeat.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Block {
+    entries: Vec<Entry>,
+}
+
+/// A single line with a key, either with a value behind the '=' or starting
+/// a block of nested entries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    // Blank lines in front of the entry
+    leading: Vec<String>,
+    // White space in front of the dots
+    indent: String,
+    key: String,
+    node: Node,
+    // The original line and its level, `None` if the entry was changed
+    raw: Option<(usize, String)>,
+    // `None` for inserted entries, which use the line ending of the document
+    ending: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Value(String),
+    Block(Block),
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Document {
+            prologue: String::new(),
+            header: None,
+            root: Block::default(),
+            epilogue: vec![],
+            newline: "\n",
+        }
+    }
+}
+
+impl Document {
+    /// An empty document with the given file header, `None` for a document
+    /// without header.
+    pub fn new(header: Option<&str>) -> Self {
+        Document {
+            prologue: header.map(|header| format!("[{}]\n", header)).unwrap_or_default(),
+            header: header.map(str::to_owned),
+            ..Default::default()
+        }
+    }
+
+    /// The section name of the file header without the brackets.
+    pub fn header(&self) -> Option<&str> {
+        self.header.as_deref()
+    }
+
+    pub fn root(&self) -> &Block {
+        &self.root
+    }
+
+    pub fn root_mut(&mut self) -> &mut Block {
+        &mut self.root
+    }
+}
+
+impl Deref for Document {
+    type Target = Block;
+
+    fn deref(&self) -> &Block {
+        &self.root
+    }
+}
+
+impl DerefMut for Document {
+    fn deref_mut(&mut self) -> &mut Block {
+        &mut self.root
+    }
+}
+
+impl FromStr for Document {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let mut parser = Parser {
+            lines: input.split_inclusive('\n').map(RawLine::new).peekable(),
+            leading: vec![],
+        };
+        let newline = match input.find('\n') {
+            Some(pos) if input[..pos].ends_with('\r') => "\r\n",
+            _ => "\n",
+        };
+
+        let mut prologue = String::new();
+        let mut header = None;
+        if let Some(RawLine { line: Line::Header(name), .. }) = parser.peek() {
+            header = Some((*name).to_owned());
+            prologue = parser.leading.concat();
+            parser.leading.clear();
+            prologue.push_str(parser.lines.next().unwrap().text);
+        }
+
+        let root = parser.parse_block(0)?;
+        match parser.peek() {
+            None => Ok(Document {
+                prologue,
+                header,
+                root,
+                epilogue: parser.leading,
+                newline,
+            }),
+            Some(RawLine { line: Line::Header(_), .. }) => Err(Error::UnexpectedHeader),
+            Some(_) => Err(Error::WrongLevel),
+        }
+    }
+}
+
+impl Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut writer = Writer {
+            f,
+            newline: self.newline,
+            missing_newline: false,
+        };
+        writer.write(&self.prologue)?;
+        writer.write_block(&self.root, 0)?;
+        for line in &self.epilogue {
+            writer.write(line)?;
+        }
+        Ok(())
+    }
+}
+
+impl Block {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Entry> {
+        self.entries.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Entry> {
+        self.entries.iter_mut()
+    }
+
+    /// The first entry with `key`.
+    pub fn get(&self, key: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.key == key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Entry> {
+        self.entries.iter_mut().find(|entry| entry.key == key)
+    }
+
+    /// All entries with `key`, e.g. every `lokomotive` of a file.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Entry> + 'a {
+        self.entries.iter().filter(move |entry| entry.key == key)
+    }
+
+    pub fn get_all_mut<'a>(&'a mut self, key: &'a str) -> impl Iterator<Item = &'a mut Entry> + 'a {
+        self.entries.iter_mut().filter(move |entry| entry.key == key)
+    }
+
+    /// The value of the first entry with `key`, `None` if there is no such
+    /// entry or it is a block.
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.get(key)?.value()
+    }
+
+    /// Set the value of the first entry with `key`, a new entry is appended
+    /// to the block if there is none.
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.get_mut(key) {
+            Some(entry) => entry.set_value(value),
+            None => {
+                self.push(Entry::with_value(key, value));
+            }
+        }
+    }
+
+    /// The index of the first entry with `key`.
+    pub fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.key == key)
+    }
+
+    /// Insert an entry in front of the entry at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, entry: Entry) -> &mut Entry {
+        self.entries.insert(index, entry);
+        &mut self.entries[index]
+    }
+
+    pub fn push(&mut self, entry: Entry) -> &mut Entry {
+        self.insert(self.entries.len(), entry)
+    }
+
+    /// Remove the first entry with `key` including the blank lines in front
+    /// of it.
+    pub fn remove(&mut self, key: &str) -> Option<Entry> {
+        let index = self.position(key)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Remove the entry at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_at(&mut self, index: usize) -> Entry {
+        self.entries.remove(index)
+    }
+
+    /// Keep only the entries for which `f` returns true.
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Entry) -> bool,
+    {
+        self.entries.retain(f)
+    }
+}
+
+impl<'a> IntoIterator for &'a Block {
+    type Item = &'a Entry;
+    type IntoIter = std::slice::Iter<'a, Entry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Entry {
+    /// A new `key=value` line.
+    pub fn with_value(key: &str, value: &str) -> Self {
+        Entry::new(key, Node::Value(value.to_owned()))
+    }
+
+    /// A new block, entries are added with `block_mut`.
+    pub fn with_block(key: &str) -> Self {
+        Entry::new(key, Node::Block(Block::default()))
+    }
+
+    fn new(key: &str, node: Node) -> Self {
+        Entry {
+            leading: vec![],
+            indent: String::new(),
+            key: key.to_owned(),
+            node,
+            raw: None,
+            ending: None,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn set_key(&mut self, key: &str) {
+        self.key = key.to_owned();
+        self.raw = None;
+    }
+
+    /// The text behind the '=', `None` for a block.
+    pub fn value(&self) -> Option<&str> {
+        match &self.node {
+            Node::Value(value) => Some(value),
+            Node::Block(_) => None,
+        }
+    }
+
+    /// Set the text behind the '='. A block is replaced by the value.
+    pub fn set_value(&mut self, value: &str) {
+        self.node = Node::Value(value.to_owned());
+        self.raw = None;
+    }
+
+    pub fn block(&self) -> Option<&Block> {
+        match &self.node {
+            Node::Value(_) => None,
+            Node::Block(block) => Some(block),
+        }
+    }
+
+    pub fn block_mut(&mut self) -> Option<&mut Block> {
+        match &mut self.node {
+            Node::Value(_) => None,
+            Node::Block(block) => Some(block),
+        }
+    }
+
+    /// Turn the entry into an empty block, replacing its value.
+    pub fn set_block(&mut self) -> &mut Block {
+        if let Node::Value(_) = self.node {
+            self.node = Node::Block(Block::default());
+            self.raw = None;
+        }
+        match &mut self.node {
+            Node::Block(block) => block,
+            Node::Value(_) => unreachable!(),
+        }
+    }
+}
+
+// One line of the input split into its content and the line ending.
+struct RawLine<'a> {
+    text: &'a str,
+    content: &'a str,
+    ending: &'a str,
+    line: Line<'a>,
+}
+
+impl<'a> RawLine<'a> {
+    fn new(text: &'a str) -> Self {
+        let content = text
+            .strip_suffix('\n')
+            .map(|content| content.strip_suffix('\r').unwrap_or(content))
+            .unwrap_or(text);
+        RawLine {
+            text,
+            content,
+            ending: &text[content.len()..],
+            line: Line::parse(content),
+        }
+    }
+}
+
+struct Parser<'a, I>
+where
+    I: Iterator<Item = RawLine<'a>>,
+{
+    lines: Peekable<I>,
+    // Blank lines which belong to the next entry
+    leading: Vec<String>,
+}
+
+impl<'a, I> Parser<'a, I>
+where
+    I: Iterator<Item = RawLine<'a>>,
+{
+    // Look at the next line which is not blank.
+    fn peek(&mut self) -> Option<&RawLine<'a>> {
+        while let Some(RawLine { line: Line::Blank, .. }) = self.lines.peek() {
+            let blank = self.lines.next().unwrap();
+            self.leading.push(blank.text.to_owned());
+        }
+        self.lines.peek()
+    }
+
+    // Collect all entries of one nesting level like `parse::parse_block`.
+    fn parse_block(&mut self, level: usize) -> Result<Block> {
+        let mut entries = vec![];
+        while let Some(&RawLine { line: Line::Entry { level: line_level, .. }, .. }) = self.peek() {
+            if line_level < level {
+                break;
+            }
+            if line_level > level {
+                return Err(Error::WrongLevel);
+            }
+            let raw = self.lines.next().unwrap();
+            let Line::Entry { key, value, .. } = raw.line else {
+                unreachable!()
+            };
+            let leading = std::mem::take(&mut self.leading);
+            let node = match value {
+                Some(value) => Node::Value(value.to_owned()),
+                None => Node::Block(self.parse_block(level + 1)?),
+            };
+            let indent = &raw.content[..raw.content.len() - raw.content.trim_start().len()];
+            entries.push(Entry {
+                leading,
+                indent: indent.to_owned(),
+                key: key.to_owned(),
+                node,
+                raw: Some((level, raw.content.to_owned())),
+                ending: Some(raw.ending.to_owned()),
+            });
+        }
+        Ok(Block { entries })
+    }
+}
+
+const DOTS: &str = "................................";
+
+struct Writer<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    newline: &'static str,
+    // The last line written had no line ending, which is only fine at the
+    // end of the document
+    missing_newline: bool,
+}
+
+impl Writer<'_, '_> {
+    fn write(&mut self, text: &str) -> fmt::Result {
+        if text.is_empty() {
+            return Ok(());
+        }
+        if self.missing_newline {
+            self.f.write_str(self.newline)?;
+        }
+        self.missing_newline = !text.ends_with('\n');
+        self.f.write_str(text)
+    }
+
+    fn write_block(&mut self, block: &Block, level: usize) -> fmt::Result {
+        for entry in &block.entries {
+            for line in &entry.leading {
+                self.write(line)?;
+            }
+            let mut line = match &entry.raw {
+                Some((raw_level, raw)) if *raw_level == level => raw.clone(),
+                _ => entry.line(level),
+            };
+            line.push_str(entry.ending.as_deref().unwrap_or(self.newline));
+            self.write(&line)?;
+            if let Node::Block(block) = &entry.node {
+                self.write_block(block, level + 1)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Entry {
+    // Build the line of a changed or inserted entry. Inserted entries are
+    // indented like the `Serializer` does.
+    fn line(&self, level: usize) -> String {
+        let mut line = match (&self.raw, &self.ending) {
+            (None, None) if level > 0 => " ".to_owned(),
+            _ => self.indent.clone(),
+        };
+        let mut dots = level;
+        while dots > DOTS.len() {
+            line.push_str(DOTS);
+            dots -= DOTS.len();
+        }
+        line.push_str(&DOTS[..dots]);
+        line.push_str(&self.key);
+        if let Node::Value(value) = &self.node {
+            line.push('=');
+            line.push_str(value);
+        }
+        line
+    }
+}
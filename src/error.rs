@@ -1,4 +1,3 @@
-use std;
 use std::fmt::{self, Display};
 
 use serde::{de, ser};
@@ -24,12 +23,20 @@ pub enum Error {
     Eof,
     ExpectedBoolean,
     ExpectedInteger,
+    ExpectedFloat,
+    ExpectedChar,
     ExpectedString,
     ExpectedArraySeperator,
     ExpectedValueSeperator,
     ExpectedNewline,
     ExpectedStructName,
     WrongLevel,
+    ExpectedHeader,
+    UnexpectedHeader,
+    WrongHeader { expected: String, found: String },
+    DuplicateKey,
+    TrailingCharacters,
+    InvalidPath { path: String, position: usize },
 }
 
 impl ser::Error for Error {
@@ -51,12 +58,24 @@ impl Display for Error {
             Error::Eof => formatter.write_str("unexpected end of input"),
             Error::ExpectedBoolean => formatter.write_str("expected bool"),
             Error::ExpectedInteger => formatter.write_str("expected integer"),
+            Error::ExpectedFloat => formatter.write_str("expected float"),
+            Error::ExpectedChar => formatter.write_str("expected single character"),
             Error::ExpectedString => formatter.write_str("expected String"),
             Error::ExpectedArraySeperator => formatter.write_str("expected array seperator (Blank)"),
             Error::ExpectedValueSeperator => formatter.write_str("expected value seperator (=)"),
             Error::ExpectedNewline => formatter.write_str("expected newline"),
             Error::ExpectedStructName => formatter.write_str("expected struct name"),
             Error::WrongLevel => formatter.write_str("wrong indention level"),
+            Error::ExpectedHeader => formatter.write_str("expected file header"),
+            Error::UnexpectedHeader => formatter.write_str("unexpected file header"),
+            Error::WrongHeader { expected, found } => {
+                write!(formatter, "expected file header [{}], found [{}]", expected, found)
+            }
+            Error::DuplicateKey => formatter.write_str("key is only allowed once in a block"),
+            Error::TrailingCharacters => formatter.write_str("unexpected characters after value"),
+            Error::InvalidPath { path, position } => {
+                write!(formatter, "invalid path `{}` at position {}", path, position)
+            }
         }
     }
 }
@@ -0,0 +1,278 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, DeserializeSeed, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use serde::ser::{Serialize, SerializeStruct};
+
+use crate::error::{Error, Result};
+
+// The `Serializer` and `Deserializer` recognize a file by this struct name,
+// comparable to the way the `toml` crate handles its Datetime type.
+pub(crate) const NAME: &str = "$serde_cs2::File";
+pub(crate) const HEADER: &str = "header";
+pub(crate) const BODY: &str = "body";
+
+/// The file header a body type belongs to.
+///
+/// Deserializing a `File<T>` compares the header of the file with
+/// `T::HEADER` and fails with `Error::WrongHeader` if they differ. Types
+/// which can be the body of any file, like maps, have no header of their
+/// own.
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_cs2::{Error, File, Header};
+///
+/// #[derive(Deserialize)]
+/// struct Lokomotiven {
+///     version: std::collections::BTreeMap<String, u32>,
+/// }
+///
+/// impl Header for Lokomotiven {
+///     const HEADER: Option<&'static str> = Some("lokomotive");
+/// }
+///
+/// let file: File<Lokomotiven> =
+///     serde_cs2::from_str("[lokomotive]\nversion\n .minor=3\n").unwrap();
+/// assert_eq!(file.body().version["minor"], 3);
+/// assert!(matches!(
+///     serde_cs2::from_str::<File<Lokomotiven>>("[magnetartikel]\nversion\n .minor=1\n"),
+///     Err(Error::WrongHeader { .. })
+/// ));
+/// ```
+pub trait Header {
+    /// The section name of the header without the brackets, `None` accepts
+    /// any header.
+    const HEADER: Option<&'static str>;
+}
+
+impl<K, V> Header for BTreeMap<K, V> {
+    const HEADER: Option<&'static str> = None;
+}
+
+impl<K, V, S> Header for HashMap<K, V, S> {
+    const HEADER: Option<&'static str> = None;
+}
+
+/// A complete cs2 file, consisting of the file header e.g. `[lokomotive]`
+/// in the first line followed by the entries on the top level.
+///
+/// The entries are represented by `T`, each field of `T` is a key on the
+/// top level of the file.
+///
+/// ```
+/// use serde_cs2::File;
+///
+/// let file: File<std::collections::BTreeMap<String, String>> =
+///     serde_cs2::from_str("[lokomotive]\nversion=3\n").unwrap();
+/// assert_eq!(file.header(), "lokomotive");
+/// assert_eq!(file.body()["version"], "3");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct File<T> {
+    header: String,
+    body: T,
+}
+
+impl<T> File<T> {
+    pub fn new<S: Into<String>>(header: S, body: T) -> Self {
+        File {
+            header: header.into(),
+            body,
+        }
+    }
+
+    /// A file with the header of the body type, an empty header if the
+    /// type has none.
+    pub fn from_body(body: T) -> Self
+    where
+        T: Header,
+    {
+        File::new(T::HEADER.unwrap_or_default(), body)
+    }
+
+    /// The section name of the header without the brackets.
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+
+    pub fn body(&self) -> &T {
+        &self.body
+    }
+
+    pub fn body_mut(&mut self) -> &mut T {
+        &mut self.body
+    }
+
+    pub fn into_body(self) -> T {
+        self.body
+    }
+
+    /// Check the section name of the header, returns `Error::WrongHeader` if
+    /// the file has a different header.
+    pub fn expect_header(self, header: &str) -> Result<Self> {
+        if self.header == header {
+            Ok(self)
+        } else {
+            Err(Error::WrongHeader {
+                expected: header.to_owned(),
+                found: self.header,
+            })
+        }
+    }
+}
+
+impl<T> Serialize for File<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut file = serializer.serialize_struct(NAME, 2)?;
+        file.serialize_field(HEADER, &self.header)?;
+        file.serialize_field(BODY, &self.body)?;
+        file.end()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for File<T>
+where
+    T: Deserialize<'de> + Header,
+{
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(NAME, &[HEADER, BODY], FileVisitor(PhantomData))
+    }
+}
+
+struct FileVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for FileVisitor<T>
+where
+    T: Deserialize<'de> + Header,
+{
+    type Value = File<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a cs2 file with header")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut header = None;
+        let mut body = None;
+        while let Some(key) = map.next_key::<&str>()? {
+            match key {
+                HEADER => header = Some(map.next_value_seed(HeaderSeed(T::HEADER))?),
+                BODY => body = Some(map.next_value()?),
+                _ => return Err(de::Error::unknown_field(key, &[HEADER, BODY])),
+            }
+        }
+        Ok(File {
+            header: header.ok_or_else(|| de::Error::missing_field(HEADER))?,
+            body: body.ok_or_else(|| de::Error::missing_field(BODY))?,
+        })
+    }
+}
+
+// The header is requested as newtype struct named like the expected header,
+// the cs2 deserializers answer with `Error::WrongHeader` if it differs, see
+// `HeaderDeserializer`. Other formats hand out the plain string.
+struct HeaderSeed(Option<&'static str>);
+
+impl<'de> DeserializeSeed<'de> for HeaderSeed {
+    type Value = String;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<String, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match self.0 {
+            Some(expected) => deserializer.deserialize_newtype_struct(expected, HeaderVisitor(self.0)),
+            None => deserializer.deserialize_string(HeaderVisitor(None)),
+        }
+    }
+}
+
+struct HeaderVisitor(Option<&'static str>);
+
+impl<'de> Visitor<'de> for HeaderVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a file header")
+    }
+
+    fn visit_str<E>(self, v: &str) -> std::result::Result<String, E>
+    where
+        E: de::Error,
+    {
+        match self.0 {
+            Some(expected) if expected != v => {
+                Err(de::Error::custom(Error::WrongHeader {
+                    expected: expected.to_owned(),
+                    found: v.to_owned(),
+                }))
+            }
+            _ => Ok(v.to_owned()),
+        }
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> std::result::Result<String, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_string(self)
+    }
+}
+
+// The header of a file for `HeaderSeed`. It is compared with the expected
+// header on the request of a newtype struct, any other request gets the
+// header as string.
+pub(crate) struct HeaderDeserializer<'de> {
+    found: &'de str,
+}
+
+impl<'de> HeaderDeserializer<'de> {
+    pub(crate) fn new(found: &'de str) -> Self {
+        HeaderDeserializer { found }
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for HeaderDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_borrowed_str(self.found)
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name != self.found {
+            return Err(Error::WrongHeader {
+                expected: name.to_owned(),
+                found: self.found.to_owned(),
+            });
+        }
+        visitor.visit_borrowed_str(self.found)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
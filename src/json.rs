@@ -0,0 +1,75 @@
+//! Conversion between cs2 and JSON, enabled by the `json` feature.
+//!
+//! Blocks become objects, repeated keys arrays and values strings. A file
+//! header is the only key of the top level object in brackets.
+//!
+//! The text functions keep the order of the keys. A `serde_json::Value`
+//! sorts them unless the `preserve_order` feature enables the feature of
+//! the same name of `serde_json`.
+//!
+//! ```
+//! let cs2 = "[lokomotive]\nlokomotive\n .name=Lok\n .funktionen\n ..nr=0\n .funktionen\n ..nr=1\n";
+//! let json = serde_cs2::json::to_string(cs2).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"[lokomotive]":{"lokomotive":{"name":"Lok","funktionen":[{"nr":"0"},{"nr":"1"}]}}}"#
+//! );
+//! assert_eq!(serde_cs2::json::from_str(&json).unwrap(), cs2);
+//! ```
+
+use std::io;
+
+use crate::bridge;
+pub use crate::bridge::Mapping;
+use crate::error::{Error, Result};
+
+/// Convert a cs2 document into JSON text.
+pub fn to_string(cs2: &str) -> Result<String> {
+    to_string_with(cs2, &Mapping::default())
+}
+
+pub fn to_string_with(cs2: &str, mapping: &Mapping) -> Result<String> {
+    let mut json = Vec::new();
+    to_writer_with(&mut json, cs2, mapping)?;
+    String::from_utf8(json).map_err(|e| Error::Message(e.to_string()))
+}
+
+/// Write a cs2 document as JSON into `writer`.
+pub fn to_writer_with<W>(writer: W, cs2: &str, mapping: &Mapping) -> Result<()>
+where
+    W: io::Write,
+{
+    let mut serializer = serde_json::Serializer::new(writer);
+    bridge::serialize(cs2, mapping, &mut serializer)
+}
+
+/// Convert a cs2 document into a `serde_json::Value`.
+pub fn to_value(cs2: &str) -> Result<serde_json::Value> {
+    to_value_with(cs2, &Mapping::default())
+}
+
+pub fn to_value_with(cs2: &str, mapping: &Mapping) -> Result<serde_json::Value> {
+    bridge::serialize(cs2, mapping, serde_json::value::Serializer)
+}
+
+/// Convert JSON text into a cs2 document. The top level has to be an
+/// object, `null` is not supported.
+pub fn from_str(json: &str) -> Result<String> {
+    from_str_with(json, &Mapping::default())
+}
+
+pub fn from_str_with(json: &str, mapping: &Mapping) -> Result<String> {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let cs2 = bridge::transcode(&mut deserializer, mapping)?;
+    deserializer.end().map_err(|e| Error::Message(e.to_string()))?;
+    Ok(cs2)
+}
+
+/// Convert a `serde_json::Value` into a cs2 document.
+pub fn from_value(json: &serde_json::Value) -> Result<String> {
+    from_value_with(json, &Mapping::default())
+}
+
+pub fn from_value_with(json: &serde_json::Value, mapping: &Mapping) -> Result<String> {
+    bridge::to_string(json, mapping)
+}
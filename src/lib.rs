@@ -1,8 +1,44 @@
 pub use crate::de::{from_str, Deserializer};
+pub use crate::diff::{diff, diff_with, Change, ChangeKind, Diff, Identity};
+pub use crate::document::Document;
 pub use crate::error::{Error, Result};
-pub use crate::ser::{to_string, Serializer};
+pub use crate::file::{File, Header};
+pub use crate::map::Map;
+pub use crate::merge::{merge, merge_str, merge_with, Conflict, Merge};
+pub use crate::query::{select, Match, Position, Query};
+pub use crate::schema::Schema;
+pub use crate::ser::{append_to_writer, insert_into_string, to_string, to_writer, Serializer};
+pub use crate::value::{from_value, to_value, Value};
 
+#[cfg(any(feature = "json", feature = "yaml"))]
+mod bridge;
 mod de;
+mod diff;
+pub mod document;
 mod error;
+mod file;
+#[cfg(feature = "json")]
+pub mod json;
+mod macros;
+mod map;
+mod merge;
+#[cfg(feature = "models")]
+pub mod models;
+mod parse;
+mod query;
+mod schema;
 mod ser;
+mod value;
+#[cfg(feature = "yaml")]
+pub mod yaml;
 
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::value::literal::{expr, literal};
+}
+
+// The examples of the README run as doctests, some of them need the optional
+// features.
+#[cfg(all(doctest, feature = "json", feature = "yaml", feature = "models"))]
+#[doc = include_str!("../README.adoc")]
+struct ReadmeDoctests;
@@ -0,0 +1,108 @@
+/// Build a `Value` with the structure of a cs2 document.
+///
+/// Blocks are written in braces, values behind a colon. Repeated entries,
+/// e.g. the `funktionen` of a lokomotive, are listed in brackets and become
+/// one entry per element like in a parsed document. A file header is given
+/// in brackets in front of the entries.
+///
+/// Values are literals or expressions in parentheses. Integer literals keep
+/// their hexadecimal notation, booleans are written as `1` and `0`.
+///
+/// ```
+/// use serde_cs2::cs2;
+///
+/// let nr = 1;
+/// let lok = cs2! {
+///     [lokomotive]
+///     lokomotive {
+///         name: "Lok",
+///         uid: 0x4001,
+///         funktionen [ { nr: 0, typ: 1 }, { nr: (nr), dauer: -1 } ],
+///     }
+/// };
+/// assert_eq!(
+///     serde_cs2::to_string(&lok).unwrap(),
+///     "[lokomotive]\nlokomotive\n .name=Lok\n .uid=0x4001\n .funktionen\n ..nr=0\n ..typ=1\n .funktionen\n ..nr=1\n ..dauer=-1\n"
+/// );
+/// ```
+#[macro_export]
+macro_rules! cs2 {
+    ([$header:ident] $($body:tt)*) => {
+        $crate::Value::Block(::std::vec![(
+            ::std::string::String::from(::std::concat!("[", ::std::stringify!($header), "]")),
+            $crate::cs2!($($body)*),
+        )])
+    };
+    ($($body:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut entries = ::std::vec::Vec::new();
+        $crate::__cs2_entries!(entries $($body)*);
+        $crate::Value::Block(entries)
+    }};
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __cs2_entries {
+    ($entries:ident) => {};
+    ($entries:ident , $($rest:tt)*) => {
+        $crate::__cs2_entries!($entries $($rest)*);
+    };
+    ($entries:ident $key:ident { $($block:tt)* } $($rest:tt)*) => {
+        $entries.push(($crate::__cs2_key!($key), $crate::cs2!($($block)*)));
+        $crate::__cs2_entries!($entries $($rest)*);
+    };
+    ($entries:ident $key:ident [ $($elements:tt)* ] $($rest:tt)*) => {
+        $crate::__cs2_elements!($entries $key $($elements)*);
+        $crate::__cs2_entries!($entries $($rest)*);
+    };
+    ($entries:ident $key:ident : ($value:expr) $($rest:tt)*) => {
+        $entries.push(($crate::__cs2_key!($key), $crate::__private::expr(&$value)));
+        $crate::__cs2_entries!($entries $($rest)*);
+    };
+    ($entries:ident $key:ident : $value:literal $($rest:tt)*) => {
+        $entries.push(($crate::__cs2_key!($key), $crate::__cs2_literal!($value)));
+        $crate::__cs2_entries!($entries $($rest)*);
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __cs2_elements {
+    ($entries:ident $key:ident) => {};
+    ($entries:ident $key:ident , $($rest:tt)*) => {
+        $crate::__cs2_elements!($entries $key $($rest)*);
+    };
+    ($entries:ident $key:ident { $($block:tt)* } $($rest:tt)*) => {
+        $entries.push(($crate::__cs2_key!($key), $crate::cs2!($($block)*)));
+        $crate::__cs2_elements!($entries $key $($rest)*);
+    };
+    ($entries:ident $key:ident ($value:expr) $($rest:tt)*) => {
+        $entries.push(($crate::__cs2_key!($key), $crate::__private::expr(&$value)));
+        $crate::__cs2_elements!($entries $key $($rest)*);
+    };
+    ($entries:ident $key:ident $value:literal $($rest:tt)*) => {
+        $entries.push(($crate::__cs2_key!($key), $crate::__cs2_literal!($value)));
+        $crate::__cs2_elements!($entries $key $($rest)*);
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __cs2_key {
+    ($key:ident) => {
+        ::std::string::String::from(::std::stringify!($key))
+    };
+}
+
+// The value of an unsuffixed integer literal is an `i32`, only its text is
+// used.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __cs2_literal {
+    ($value:literal) => {{
+        #[allow(overflowing_literals)]
+        let value = $value;
+        $crate::__private::literal(::std::stringify!($value), value)
+    }};
+}
@@ -0,0 +1,254 @@
+use std::fmt;
+
+use serde::de::{Deserialize, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap};
+
+use crate::file::Header;
+use crate::value::Value;
+
+/// The entries of a block in their original order, keys might repeat.
+///
+/// Unlike a `HashMap` or `BTreeMap` a `Map` keeps the order of the keys,
+/// which some CS2 tools rely on, and every entry of a repeated key. It is
+/// used for blocks without a fixed structure, as field of a struct or with
+/// `#[serde(flatten)]` to capture the keys the struct doesn't know.
+///
+/// ```
+/// use serde_cs2::{Map, Value};
+///
+/// let mut map: Map = serde_cs2::from_str("vmax=255\nlok=A\nname=Lok\nlok=B\n").unwrap();
+/// assert_eq!(map.get("name"), Some(&Value::from("Lok")));
+/// assert_eq!(map.get_all("lok").count(), 2);
+///
+/// map.set("vmax", "100");
+/// map.push("lok", "C");
+/// assert_eq!(
+///     serde_cs2::to_string(&map).unwrap(),
+///     "vmax=100\nlok=A\nname=Lok\nlok=B\nlok=C\n"
+/// );
+/// ```
+///
+/// Captured by `#[serde(flatten)]` the unknown entries are grouped by key
+/// like for any other map, repeated keys stay together at the position of
+/// their first entry. They are written behind the fields of the struct.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Map {
+    entries: Vec<(String, Value)>,
+}
+
+impl Header for Map {
+    const HEADER: Option<&'static str> = None;
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Map::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, (String, Value)> {
+        self.entries.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, (String, Value)> {
+        self.entries.iter_mut()
+    }
+
+    /// The value of the first entry with `key`.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| entry == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.entries
+            .iter_mut()
+            .find(|(entry, _)| entry == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The values of all entries with `key` in their order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Value> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(entry, _)| entry == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Set the value of the first entry with `key`, a new entry is appended
+    /// if there is none. Returns the previous value.
+    pub fn set<V: Into<Value>>(&mut self, key: &str, value: V) -> Option<Value> {
+        match self.get_mut(key) {
+            Some(entry) => Some(std::mem::replace(entry, value.into())),
+            None => {
+                self.push(key, value);
+                None
+            }
+        }
+    }
+
+    /// The index of the first entry with `key`.
+    pub fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|(entry, _)| entry == key)
+    }
+
+    /// Insert an entry in front of the entry at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert<K: Into<String>, V: Into<Value>>(&mut self, index: usize, key: K, value: V) {
+        self.entries.insert(index, (key.into(), value.into()));
+    }
+
+    /// Append an entry, even if there are entries with `key` already.
+    pub fn push<K: Into<String>, V: Into<Value>>(&mut self, key: K, value: V) {
+        self.entries.push((key.into(), value.into()));
+    }
+
+    /// Remove the first entry with `key`.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.position(key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Remove the entry at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_at(&mut self, index: usize) -> (String, Value) {
+        self.entries.remove(index)
+    }
+
+    /// Keep only the entries for which `f` returns true.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &Value) -> bool,
+    {
+        self.entries.retain(|(key, value)| f(key, value));
+    }
+}
+
+// Indexing by key, missing keys return an empty block like for `Value`.
+impl std::ops::Index<&str> for Map {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        static EMPTY: Value = Value::Block(Vec::new());
+        self.get(key).unwrap_or(&EMPTY)
+    }
+}
+
+impl From<Vec<(String, Value)>> for Map {
+    fn from(entries: Vec<(String, Value)>) -> Self {
+        Map { entries }
+    }
+}
+
+impl From<Map> for Value {
+    fn from(map: Map) -> Self {
+        Value::Block(map.entries)
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Map {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> Extend<(K, V)> for Map {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.push(key, value);
+        }
+    }
+}
+
+impl IntoIterator for Map {
+    type Item = (String, Value);
+    type IntoIter = std::vec::IntoIter<(String, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = &'a (String, Value);
+    type IntoIter = std::slice::Iter<'a, (String, Value)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+impl Serialize for Map {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+        for (key, value) in &self.entries {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+// Without a type hint a block is deserialized with all entries in their
+// order, so `deserialize_any` keeps repeated keys apart.
+impl<'de> Deserialize<'de> for Map {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(MapVisitor)
+    }
+}
+
+struct MapVisitor;
+
+impl<'de> Visitor<'de> for MapVisitor {
+    type Value = Map;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a cs2 block")
+    }
+
+    // Grouped repeated keys, e.g. from a flattened struct, are split into
+    // one entry per value again.
+    fn visit_map<A>(self, mut access: A) -> Result<Map, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut map = Map::new();
+        while let Some((key, value)) = access.next_entry::<String, Value>()? {
+            match value {
+                Value::List(values) => {
+                    for value in values {
+                        map.push(key.clone(), value);
+                    }
+                }
+                value => map.push(key, value),
+            }
+        }
+        Ok(map)
+    }
+}
@@ -0,0 +1,192 @@
+// Three way merge of documents.
+//
+// Blocks are matched by identity like in `diff`. Every key is merged on its
+// own: a change on one side is taken over, the same change on both sides is
+// taken once and different changes on both sides are a conflict. The result
+// keeps our value for conflicts.
+
+use crate::de::from_str;
+use crate::diff::{header, keys, match_blocks, Identity};
+use crate::error::Result;
+use crate::value::Value;
+
+/// The result of `merge`, the merged document and the conflicts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Merge {
+    value: Value,
+    conflicts: Vec<Conflict>,
+}
+
+/// Different changes of the same entry on both sides. `None` stands for an
+/// entry which is missing, e.g. removed on one side.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    pub path: String,
+    pub base: Option<Value>,
+    pub ours: Option<Value>,
+    pub theirs: Option<Value>,
+}
+
+/// Merge the changes of `ours` and `theirs` relative to `base`, matching
+/// repeated blocks with the default `Identity`.
+///
+/// ```
+/// let base = serde_cs2::from_str("lokomotive\n .name=Lok\n .adresse=0x5\n .vmax=80\n").unwrap();
+/// let ours = serde_cs2::from_str("lokomotive\n .name=Lok\n .adresse=0x6\n .vmax=80\n").unwrap();
+/// let theirs = serde_cs2::from_str("lokomotive\n .name=Lok\n .adresse=0x5\n .vmax=100\n").unwrap();
+///
+/// let merge = serde_cs2::merge(&base, &ours, &theirs);
+/// assert!(merge.is_clean());
+/// assert_eq!(
+///     serde_cs2::to_string(merge.value()).unwrap(),
+///     "lokomotive\n .name=Lok\n .adresse=0x6\n .vmax=100\n"
+/// );
+/// ```
+pub fn merge(base: &Value, ours: &Value, theirs: &Value) -> Merge {
+    merge_with(base, ours, theirs, &Identity::default())
+}
+
+/// Merge with `identity` matching the repeated blocks.
+pub fn merge_with(base: &Value, ours: &Value, theirs: &Value, identity: &Identity) -> Merge {
+    let mut merger = Merger {
+        identity,
+        conflicts: vec![],
+    };
+    let value = match (header(base), header(ours), header(theirs)) {
+        (Some((header, base)), Some((ours_header, ours)), Some((theirs_header, theirs)))
+            if header == ours_header && header == theirs_header =>
+        {
+            let body = merger.block(&mut String::new(), base, ours, theirs);
+            Value::Block(vec![(header.to_owned(), body)])
+        }
+        _ => merger.block(&mut String::new(), base, ours, theirs),
+    };
+    Merge {
+        value,
+        conflicts: merger.conflicts,
+    }
+}
+
+/// Parse the three documents and merge them, see `merge`.
+pub fn merge_str(base: &str, ours: &str, theirs: &str) -> Result<Merge> {
+    let base: Value = from_str(base)?;
+    let ours: Value = from_str(ours)?;
+    let theirs: Value = from_str(theirs)?;
+    Ok(merge(&base, &ours, &theirs))
+}
+
+impl Merge {
+    /// True if there are no conflicts.
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    pub fn into_value(self) -> Value {
+        self.value
+    }
+
+    pub fn conflicts(&self) -> &[Conflict] {
+        &self.conflicts
+    }
+}
+
+struct Merger<'a> {
+    identity: &'a Identity,
+    conflicts: Vec<Conflict>,
+}
+
+impl Merger<'_> {
+    // Merge three blocks. The keys are in our order, followed by the keys
+    // which are new in theirs.
+    fn block(&mut self, path: &mut String, base: &Value, ours: &Value, theirs: &Value) -> Value {
+        let mut entries = vec![];
+        for key in keys(&[ours, theirs, base]) {
+            let base: Vec<&Value> = base.get_all(key).collect();
+            let ours: Vec<&Value> = ours.get_all(key).collect();
+            let theirs: Vec<&Value> = theirs.get_all(key).collect();
+            let len = path.len();
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(key);
+            for value in self.entries(path, &base, &ours, &theirs) {
+                entries.push((key.to_owned(), value));
+            }
+            path.truncate(len);
+        }
+        Value::Block(entries)
+    }
+
+    // Merge all values of a key. Scalars are merged as a whole, so repeated
+    // entries like the `lok`s of a `traktion` are one value.
+    fn entries(
+        &mut self,
+        path: &mut String,
+        base: &[&Value],
+        ours: &[&Value],
+        theirs: &[&Value],
+    ) -> Vec<Value> {
+        let is_block = |value: &&Value| matches!(value, Value::Block(_));
+        if ![base, ours, theirs].iter().all(|values| values.iter().all(is_block)) {
+            let (base, ours, theirs) = (single(base), single(ours), single(theirs));
+            return match self.value(path, base.as_ref(), ours.as_ref(), theirs.as_ref()) {
+                Some(Value::List(values)) => values,
+                Some(value) => vec![value],
+                None => vec![],
+            };
+        }
+
+        let mut values = vec![];
+        for (blocks, filter) in match_blocks(&[ours, theirs, base], self.identity) {
+            let len = path.len();
+            path.push_str(&filter);
+            let (ours, theirs, base) = (blocks[0], blocks[1], blocks[2]);
+            let merged = match (base, ours, theirs) {
+                (base, Some(ours), Some(theirs)) => {
+                    let empty = Value::default();
+                    Some(self.block(path, base.unwrap_or(&empty), ours, theirs))
+                }
+                (base, ours, theirs) => self.value(path, base, ours, theirs),
+            };
+            values.extend(merged);
+            path.truncate(len);
+        }
+        values
+    }
+
+    // Merge a value which can't be merged any further.
+    fn value(
+        &mut self,
+        path: &str,
+        base: Option<&Value>,
+        ours: Option<&Value>,
+        theirs: Option<&Value>,
+    ) -> Option<Value> {
+        if ours == theirs || theirs == base {
+            return ours.cloned();
+        }
+        if ours == base {
+            return theirs.cloned();
+        }
+        self.conflicts.push(Conflict {
+            path: path.to_owned(),
+            base: base.cloned(),
+            ours: ours.cloned(),
+            theirs: theirs.cloned(),
+        });
+        ours.cloned()
+    }
+}
+
+// All values of a key as one value, a `List` for repeated entries.
+fn single(values: &[&Value]) -> Option<Value> {
+    match values {
+        [] => None,
+        [value] => Some((*value).clone()),
+        values => Some(Value::List(values.iter().map(|value| (*value).clone()).collect())),
+    }
+}
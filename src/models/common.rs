@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use super::fields;
+use crate::map::Map;
+
+/// The `version` block at the beginning of most files. Most files have a
+/// `minor` version only, the track diagram a `major` version only.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Version {
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub major: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub minor: Option<u8>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// The `session` block, the id changes whenever the Central Station
+/// rewrites the file.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Session {
+    #[serde(with = "fields::number")]
+    pub id: u32,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use super::fields;
+use super::{Artikel, MagnetartikelFile, Stellung, Version};
+use crate::file::Header;
+use crate::map::Map;
+
+/// The entries of `fahrstrassen.cs2`, the body of `File<FahrstrassenFile>`
+/// with the header `[fahrstrassen]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FahrstrassenFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub fahrstrasse: Vec<Fahrstrasse>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for FahrstrassenFile {
+    const HEADER: Option<&'static str> = Some("fahrstrassen");
+}
+
+/// A route switching several articles one after the other.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Fahrstrasse {
+    #[serde(with = "fields::number")]
+    pub id: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The S88 contact triggering the route.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub s88: Option<u16>,
+    /// 1 to trigger the route when the contact gets occupied, 0 when it
+    /// gets free.
+    #[serde(rename = "s88Ein", default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub s88_ein: Option<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub item: Vec<Item>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// A step of a route, usually switching an article.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Item {
+    /// The `id` of the switched `Artikel`.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub magnetartikel: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stellung: Option<Stellung>,
+    /// The delay in seconds before the next item.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub sekunde: Option<f32>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Fahrstrasse {
+    /// The items with the articles they switch, `None` for items without
+    /// article or an article missing in `magnetartikel`.
+    pub fn resolve<'a>(
+        &'a self,
+        magnetartikel: &'a MagnetartikelFile,
+    ) -> impl Iterator<Item = (&'a Item, Option<&'a Artikel>)> + 'a {
+        self.item.iter().map(move |item| (item, item.resolve(magnetartikel)))
+    }
+}
+
+impl Item {
+    /// The article switched by the item.
+    pub fn resolve<'a>(&self, magnetartikel: &'a MagnetartikelFile) -> Option<&'a Artikel> {
+        magnetartikel.get(self.magnetartikel?)
+    }
+}
@@ -0,0 +1,195 @@
+// Helpers for `#[serde(with = ...)]` on the fields of the models.
+//
+// The models capture unknown keys with `#[serde(flatten)]`. Serde buffers
+// the entries of such a struct without type information, so every value
+// arrives as string and a single entry of a repeated key as a lone block
+// with its entries in their order. The helpers parse the text themselves
+// and accept both shapes for lists.
+
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, DeserializeOwned, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::Serializer;
+
+use crate::map::Map;
+use crate::value::Value;
+
+fn parse<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let text = String::deserialize(deserializer)?;
+    text.parse().map_err(de::Error::custom)
+}
+
+// Decimal numbers.
+pub(crate) mod number {
+    use super::*;
+
+    pub(crate) fn serialize<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        parse(deserializer)
+    }
+}
+
+pub(crate) mod number_opt {
+    use super::*;
+
+    pub(crate) fn serialize<T: Display, S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.collect_str(value),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        parse(deserializer).map(Some)
+    }
+}
+
+// Unsigned integers written in hexadecimal with `0x` prefix. The helpers
+// are implemented here instead of using `serde-hex`: its deserializer reads
+// the value as bytes, but a struct with `#[serde(flatten)]` buffers the
+// value as string and only hands out a `str`.
+pub(crate) trait Hex: Sized + Copy + std::fmt::LowerHex {
+    fn from_hex(digits: &str) -> Option<Self>;
+}
+
+macro_rules! hex {
+    ($($ty:ty)*) => {
+        $(
+            impl Hex for $ty {
+                fn from_hex(digits: &str) -> Option<Self> {
+                    <$ty>::from_str_radix(digits, 16).ok()
+                }
+            }
+        )*
+    };
+}
+
+hex! { u8 u16 u32 u64 }
+
+fn parse_hex<'de, T: Hex, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    text.strip_prefix("0x")
+        .and_then(T::from_hex)
+        .ok_or_else(|| de::Error::custom(format_args!("invalid hexadecimal number `{}`", text)))
+}
+
+// Hexadecimal like the `uid` `0x4006` or the `adresse` `0x6`, written without
+// leading zeros like the Central Station does. Leading zeros are accepted.
+pub(crate) mod hex_opt {
+    use super::*;
+
+    pub(crate) fn serialize<T: Hex, S: Serializer>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.collect_str(&format_args!("{:#x}", value)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, T: Hex, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+        parse_hex(deserializer).map(Some)
+    }
+}
+
+// Blank separated numbers of fixed length like the `blocks` of an mfx
+// lokomotive.
+pub(crate) mod array_opt {
+    use super::*;
+
+    pub(crate) fn serialize<T, S, const N: usize>(value: &Option<[T; N]>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Display,
+        S: Serializer,
+    {
+        match value {
+            Some(values) => {
+                let text: Vec<String> = values.iter().map(ToString::to_string).collect();
+                serializer.serialize_str(&text.join(" "))
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, T, D, const N: usize>(deserializer: D) -> Result<Option<[T; N]>, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        let values = text
+            .split(' ')
+            .map(|value| value.parse().map_err(de::Error::custom))
+            .collect::<Result<Vec<T>, D::Error>>()?;
+        let len = values.len();
+        values
+            .try_into()
+            .map(Some)
+            .map_err(|_| de::Error::invalid_length(len, &format!("{} values", N).as_str()))
+    }
+}
+
+// Repeated keys, a sequence or a single value.
+pub(crate) mod list {
+    use super::*;
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: DeserializeOwned,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ListVisitor(PhantomData))
+    }
+
+    struct ListVisitor<T>(PhantomData<T>);
+
+    impl<'de, T: DeserializeOwned> Visitor<'de> for ListVisitor<T> {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("one or more entries")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+            let mut values = vec![];
+            while let Some(value) = seq.next_element()? {
+                values.push(value);
+            }
+            Ok(values)
+        }
+
+        // A lone block arrives with its entries in their order, repeated
+        // keys are grouped by the `Value` again.
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Vec<T>, A::Error> {
+            let block = Value::from(Map::deserialize(de::value::MapAccessDeserializer::new(map))?);
+            T::deserialize(&block).map(|value| vec![value]).map_err(de::Error::custom)
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Vec<T>, E> {
+            T::deserialize(de::value::StrDeserializer::new(value)).map(|value| vec![value])
+        }
+
+        fn visit_borrowed_str<E: de::Error>(self, value: &'de str) -> Result<Vec<T>, E> {
+            T::deserialize(de::value::BorrowedStrDeserializer::new(value)).map(|value| vec![value])
+        }
+    }
+}
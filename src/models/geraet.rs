@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use super::Version;
+use crate::file::Header;
+use crate::map::Map;
+
+/// The entries of `geraet.vrs`, the body of `File<GeraetFile>` with the
+/// header `[geraet]`. Despite the extension the file is a cs2 document, it
+/// is recognized by the header: reading another file as `File<GeraetFile>`
+/// fails with `Error::WrongHeader`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct GeraetFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geraet: Option<Geraet>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for GeraetFile {
+    const HEADER: Option<&'static str> = Some("geraet");
+}
+
+/// The identity of a Central Station. All values are kept as written, the
+/// version numbers don't follow a common format.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Geraet {
+    /// The serial number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sernum: Option<String>,
+    /// The Märklin article number, e.g. `60215`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub articleno: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub produzent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub produktname: Option<String>,
+    /// The hardware version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardvers: Option<String>,
+    /// The software version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub softvers: Option<String>,
+    /// The uid of the track format processor (GFP).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gfpuid: Option<String>,
+    /// The uid of the user interface, the CAN link to the GFP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guiuid: Option<String>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+use super::fields;
+use super::Version;
+use crate::file::Header;
+use crate::map::Map;
+
+/// The entries of `gleisbild.cs2`, the body of `File<GleisbildFile>` with
+/// the header `[gleisbild]`. It lists the pages of the track diagram, each
+/// page is a file in the directory `gleisbilder`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct GleisbildFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub seite: Vec<Seite>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for GleisbildFile {
+    const HEADER: Option<&'static str> = Some("gleisbild");
+}
+
+/// A page of the track diagram, stored in `gleisbilder/<name>.cs2`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Seite {
+    /// The number of the page, omitted for the first page.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub id: Option<u8>,
+    pub name: String,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// The entries of a page in `gleisbilder/*.cs2`, the body of
+/// `File<GleisbildseiteFile>` with the header `[gleisbildseite]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct GleisbildseiteFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub element: Vec<Element>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for GleisbildseiteFile {
+    const HEADER: Option<&'static str> = Some("gleisbildseite");
+}
+
+/// A cell of the track diagram.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Element {
+    /// The position packed into one number, omitted for the top left cell
+    /// of the first page. See `position`.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub id: Option<u32>,
+    /// The kind of the element, e.g. `gerade`, `linksweiche` or `text`.
+    pub typ: String,
+    /// The rotation in steps of 90 degrees.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub drehung: Option<u8>,
+    /// The `id` of the `Artikel` switched by the element.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub artikel: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Element {
+    /// The page and cell of the element decoded from its `id`.
+    pub fn position(&self) -> Position {
+        Position::from_id(self.id.unwrap_or_default())
+    }
+
+    /// Move the element, the `id` is omitted for the top left cell of the
+    /// first page like the Central Station does.
+    pub fn set_position(&mut self, position: Position) {
+        let id = position.id();
+        self.id = if id == 0 { None } else { Some(id) };
+    }
+}
+
+/// The location of an `Element`: the page, the column `x` and the row `y`.
+///
+/// The `id` of an element packs it into the bytes `0xSSYYXX`.
+///
+/// ```
+/// use serde_cs2::models::Position;
+///
+/// let position = Position::from_id(0x1_0502);
+/// assert_eq!(position, Position { seite: 1, x: 2, y: 5 });
+/// assert_eq!(position.id(), 0x1_0502);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub seite: u8,
+    pub x: u8,
+    pub y: u8,
+}
+
+impl Position {
+    pub fn from_id(id: u32) -> Self {
+        Position {
+            seite: (id >> 16) as u8,
+            x: id as u8,
+            y: (id >> 8) as u8,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        u32::from(self.seite) << 16 | u32::from(self.y) << 8 | u32::from(self.x)
+    }
+}
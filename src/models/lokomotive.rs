@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+
+use super::fields;
+use super::{Protocol, Session, Traktion, Version};
+use crate::file::Header;
+use crate::map::Map;
+
+/// The entries of `lokomotive.cs2`, the body of `File<LokomotiveFile>`
+/// with the header `[lokomotive]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LokomotiveFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<Session>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub lokomotive: Vec<Lokomotive>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub traktion: Vec<Traktion>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for LokomotiveFile {
+    const HEADER: Option<&'static str> = Some("lokomotive");
+}
+
+/// A lokomotive with the keys written by the CS2 and CS3. All keys except
+/// the name are optional, the Central Station omits most of them depending
+/// on the decoder.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Lokomotive {
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub uid: Option<u16>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vorname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub adresse: Option<u16>,
+    /// The protocol, e.g. `mm2_prg` or `mfx`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<Protocol>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub sid: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub mfxuid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub symbol: Option<u8>,
+    /// Acceleration delay.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub av: Option<u16>,
+    /// Braking delay.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub bv: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub volume: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub progmask: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub velocity: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub richtung: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub tachomax: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub vmax: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub vmin: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub xprot: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub mfxtyp: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::array_opt")]
+    pub blocks: Option<[u8; 16]>,
+    /// The mfx configuration of the decoder, kept as it is.
+    #[serde(rename = "mfxAdr", default, skip_serializing_if = "Option::is_none")]
+    pub mfx_adr: Option<Map>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub funktionen: Vec<Funktion>,
+    /// The functions above 16 of a CS3.
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub funktionen_2: Vec<Funktion>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Lokomotive {
+    /// The function `nr` from `funktionen` or `funktionen_2`.
+    pub fn funktion(&self, nr: u8) -> Option<&Funktion> {
+        self.funktionen
+            .iter()
+            .chain(&self.funktionen_2)
+            .find(|funktion| funktion.nr == nr)
+    }
+}
+
+/// A function of a lokomotive.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Funktion {
+    #[serde(with = "fields::number")]
+    pub nr: u8,
+    /// The symbol with the momentary flag, see `FunctionSymbol`.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub typ: Option<u16>,
+    /// The duration of a momentary function, negative values for special
+    /// behaviour.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub dauer: Option<i16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub wert: Option<u16>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
@@ -0,0 +1,203 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::fields;
+use super::Version;
+use crate::file::Header;
+use crate::map::Map;
+
+/// The entries of `magnetartikel.cs2`, the body of
+/// `File<MagnetartikelFile>` with the header `[magnetartikel]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MagnetartikelFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub artikel: Vec<Artikel>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for MagnetartikelFile {
+    const HEADER: Option<&'static str> = Some("magnetartikel");
+}
+
+/// A turnout, signal or other accessory.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Artikel {
+    /// The decoder address, counted from 1.
+    #[serde(with = "fields::number")]
+    pub id: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<ArtikelTyp>,
+    /// The current position.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stellung: Option<Stellung>,
+    /// The switching time in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub schaltzeit: Option<u16>,
+    /// The protocol of the decoder, `mm2` or `dcc`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dectyp: Option<String>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl MagnetartikelFile {
+    /// The article with the decoder address `id`.
+    pub fn get(&self, id: u16) -> Option<&Artikel> {
+        self.artikel.iter().find(|artikel| artikel.id == id)
+    }
+}
+
+/// The position of an `Artikel`, written as its number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stellung {
+    /// 0, branch or stop.
+    Rot,
+    /// 1, straight or proceed.
+    Gruen,
+    /// 2, the third position, e.g. of a three way turnout.
+    Gelb,
+    /// 3, the fourth position.
+    Weiss,
+}
+
+impl Stellung {
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+}
+
+impl TryFrom<u8> for Stellung {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, u8> {
+        match value {
+            0 => Ok(Stellung::Rot),
+            1 => Ok(Stellung::Gruen),
+            2 => Ok(Stellung::Gelb),
+            3 => Ok(Stellung::Weiss),
+            value => Err(value),
+        }
+    }
+}
+
+impl Serialize for Stellung {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        fields::number::serialize(&self.as_u8(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Stellung {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value: u8 = fields::number::deserialize(deserializer)?;
+        Stellung::try_from(value)
+            .map_err(|value| serde::de::Error::custom(format_args!("invalid stellung {}", value)))
+    }
+}
+
+macro_rules! artikel_typ {
+    ($($variant:ident => $name:literal,)*) => {
+        /// The kind of an `Artikel`. Types unknown to this crate are kept
+        /// as `Other`.
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum ArtikelTyp {
+            $(
+                #[doc = concat!("`", $name, "`")]
+                $variant,
+            )*
+            Other(String),
+        }
+
+        impl ArtikelTyp {
+            /// The name in the file.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(ArtikelTyp::$variant => $name,)*
+                    ArtikelTyp::Other(name) => name,
+                }
+            }
+        }
+
+        impl FromStr for ArtikelTyp {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Infallible> {
+                Ok(match s {
+                    $($name => ArtikelTyp::$variant,)*
+                    other => ArtikelTyp::Other(other.to_owned()),
+                })
+            }
+        }
+    };
+}
+
+artikel_typ! {
+    StdRotGruen => "std_rot_gruen",
+    StdRot => "std_rot",
+    StdGruen => "std_gruen",
+    Linksweiche => "linksweiche",
+    Rechtsweiche => "rechtsweiche",
+    YWeiche => "y_weiche",
+    Dreiwegweiche => "dreiwegweiche",
+    Entkupplungsgleis => "entkupplungsgleis",
+    Entkupplungsgleis1 => "entkupplungsgleis_1",
+    LichtsignalHp01 => "lichtsignal_HP01",
+    LichtsignalHp02 => "lichtsignal_HP02",
+    LichtsignalHp012 => "lichtsignal_HP012",
+    LichtsignalHp012Sh01 => "lichtsignal_HP012_SH01",
+    LichtsignalSh01 => "lichtsignal_SH01",
+    FormsignalHp01 => "formsignal_HP01",
+    FormsignalHp02 => "formsignal_HP02",
+    FormsignalHp012 => "formsignal_HP012",
+    FormsignalHp012Sh01 => "formsignal_HP012_SH01",
+    FormsignalSh01 => "formsignal_SH01",
+    UrcLichtsignalHp01 => "urc_lichtsignal_HP01",
+    UrcLichtsignalHp012 => "urc_lichtsignal_HP012",
+    UrcLichtsignalHp012Sh01 => "urc_lichtsignal_HP012_SH01",
+    UrcLichtsignalSh01 => "urc_lichtsignal_SH01",
+    K84Ausgang => "k84_ausgang",
+    K84Doppelausgang => "k84_doppelausgang",
+    Digitaldrehscheibe => "digitaldrehscheibe",
+    Schiebebuehne => "schiebebuehne",
+}
+
+impl ArtikelTyp {
+    /// True for light and semaphore signals.
+    pub fn is_signal(&self) -> bool {
+        self.as_str().contains("signal_")
+    }
+
+    /// True for the turnouts.
+    pub fn is_weiche(&self) -> bool {
+        matches!(
+            self,
+            ArtikelTyp::Linksweiche | ArtikelTyp::Rechtsweiche | ArtikelTyp::YWeiche | ArtikelTyp::Dreiwegweiche
+        )
+    }
+}
+
+impl fmt::Display for ArtikelTyp {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ArtikelTyp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ArtikelTyp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(name.parse().unwrap_or_else(|never| match never {}))
+    }
+}
@@ -0,0 +1,51 @@
+//! Ready made types for the files of the Central Station 2 and 3.
+//!
+//! The types cover the keys known for the files and keep all other keys in
+//! an `unknown` map, so files of newer software versions are written back
+//! without losing anything. Unknown keys are written behind the known ones,
+//! the order of a block with unknown keys between known ones is not kept.
+//! Numbers are typed, hexadecimal values like the `uid` are written with
+//! their `0x` prefix and without leading zeros.
+//!
+//! ```
+//! use serde_cs2::models::LokomotiveFile;
+//! use serde_cs2::File;
+//!
+//! let cs2 = "[lokomotive]\nlokomotive\n .uid=0x4006\n .name=BR 218\n .adresse=0x6\n .vmax=60\n .spm=1\n";
+//! let file: File<LokomotiveFile> = serde_cs2::from_str(cs2).unwrap();
+//! let lok = &file.body().lokomotive[0];
+//! assert_eq!(lok.uid, Some(0x4006));
+//! assert_eq!(lok.vmax, Some(60));
+//! assert!(lok.unknown.contains_key("spm"));
+//! assert_eq!(serde_cs2::to_string(&file).unwrap(), cs2);
+//! ```
+//!
+//! The file types implement `Header`, deserializing a file with a different
+//! header fails with `Error::WrongHeader`.
+
+mod common;
+mod fahrstrassen;
+mod fields;
+mod geraet;
+mod gleisbild;
+mod lokomotive;
+mod magnetartikel;
+mod ms2;
+mod protocol;
+mod status;
+mod symbol;
+mod traktion;
+
+pub use self::common::{Session, Version};
+pub use self::fahrstrassen::{Fahrstrasse, FahrstrassenFile, Item};
+pub use self::geraet::{Geraet, GeraetFile};
+pub use self::gleisbild::{Element, GleisbildFile, GleisbildseiteFile, Position, Seite};
+pub use self::lokomotive::{Funktion, Lokomotive, LokomotiveFile};
+pub use self::magnetartikel::{Artikel, ArtikelTyp, MagnetartikelFile, Stellung};
+pub use self::ms2::{from_ms2_stream, to_ms2_stream, Fkt, Lokinfo, LokinfoFile, LoklisteFile, LoklisteLok, Numloks};
+pub use self::protocol::{AddressError, AddressProblem, Protocol};
+pub use self::status::{
+    ArtikelStatus, FahrstrasseStatus, FsstatFile, FunktionStatus, LokstatFile, Lokstat, MagstatFile,
+};
+pub use self::symbol::FunctionSymbol;
+pub use self::traktion::{Member, Traktion};
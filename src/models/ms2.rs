@@ -0,0 +1,203 @@
+// The Mobile Station 2 requests the lokomotives from the Central Station as
+// small texts in the format of the files. `loknamen` returns a page of the
+// lokomotive names as `[lokliste]`, `lokinfo` the data of one lokomotive as
+// `[lokomotive]` with a `lok` block and its functions as `fkt` blocks in
+// the order of their numbers. The texts arrive in frames of 8 bytes, the
+// last one padded with zeros.
+
+use serde::{Deserialize, Serialize};
+
+use super::fields;
+use super::{Lokomotive, LokomotiveFile, Protocol};
+use crate::file::Header;
+use crate::map::Map;
+
+/// The entries of a `loknamen` answer, the body of `File<LoklisteFile>`
+/// with the header `[lokliste]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LoklisteFile {
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub lok: Vec<LoklisteLok>,
+    /// The number of all lokomotives, not only of the listed ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub numloks: Option<Numloks>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for LoklisteFile {
+    const HEADER: Option<&'static str> = Some("lokliste");
+}
+
+/// A lokomotive of a `LoklisteFile`, `nr` counts all lokomotives from 0.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LoklisteLok {
+    #[serde(with = "fields::number")]
+    pub nr: usize,
+    pub name: String,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// The `numloks` block of a `LoklisteFile`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Numloks {
+    #[serde(with = "fields::number")]
+    pub wert: usize,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// The entries of a `lokinfo` answer, the body of `File<LokinfoFile>` with
+/// the header `[lokomotive]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LokinfoFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lok: Option<Lokinfo>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for LokinfoFile {
+    const HEADER: Option<&'static str> = Some("lokomotive");
+}
+
+/// A lokomotive as the Mobile Station 2 knows it, the keys are written in
+/// this order.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Lokinfo {
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub uid: Option<u16>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub adresse: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<Protocol>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub mfxuid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub av: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub bv: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub volume: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub velocity: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub richtung: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub vmax: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub vmin: Option<u16>,
+    /// The functions without number, the index is the number.
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub fkt: Vec<Fkt>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// A function of a `Lokinfo`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Fkt {
+    /// The symbol with the momentary flag, see `FunctionSymbol`.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub typ: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub wert: Option<u16>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl LoklisteFile {
+    /// The answer to `loknamen<start> <count>`: `count` lokomotives of
+    /// `file` beginning with number `start`.
+    pub fn page(file: &LokomotiveFile, start: usize, count: usize) -> Self {
+        LoklisteFile {
+            lok: file
+                .lokomotive
+                .iter()
+                .enumerate()
+                .skip(start)
+                .take(count)
+                .map(|(nr, lokomotive)| LoklisteLok {
+                    nr,
+                    name: lokomotive.name.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            numloks: Some(Numloks {
+                wert: file.lokomotive.len(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+impl Lokinfo {
+    /// The number of functions of the Mobile Station 2.
+    pub const FUNKTIONEN: u8 = 16;
+}
+
+// The answer to `lokinfo`, functions the lokomotive doesn't have are sent
+// with `typ` and `wert` 0.
+impl From<&Lokomotive> for Lokinfo {
+    fn from(lokomotive: &Lokomotive) -> Self {
+        Lokinfo {
+            uid: lokomotive.uid,
+            name: lokomotive.name.clone(),
+            adresse: lokomotive.adresse,
+            typ: lokomotive.typ.clone(),
+            mfxuid: lokomotive.mfxuid,
+            av: lokomotive.av,
+            bv: lokomotive.bv,
+            volume: lokomotive.volume,
+            velocity: lokomotive.velocity,
+            richtung: lokomotive.richtung,
+            vmax: lokomotive.vmax,
+            vmin: lokomotive.vmin,
+            fkt: (0..Lokinfo::FUNKTIONEN)
+                .map(|nr| {
+                    let funktion = lokomotive.funktion(nr);
+                    Fkt {
+                        typ: Some(funktion.and_then(|funktion| funktion.typ).unwrap_or_default()),
+                        wert: Some(funktion.and_then(|funktion| funktion.wert).unwrap_or_default()),
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Deserialize a text received from a Mobile Station 2, the zeros padding
+/// the last frame are ignored.
+///
+/// ```
+/// use serde_cs2::models::{from_ms2_stream, LoklisteFile};
+/// use serde_cs2::File;
+///
+/// let stream = b"[lokliste]\nlok\n .nr=0\n .name=BR 218\nnumloks\n .wert=1\n\0\0\0";
+/// let file: File<LoklisteFile> = from_ms2_stream(stream).unwrap();
+/// assert_eq!(file.body().lok[0].name, "BR 218");
+/// ```
+pub fn from_ms2_stream<'a, T>(bytes: &'a [u8]) -> crate::Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let len = bytes.iter().rposition(|byte| *byte != 0).map_or(0, |last| last + 1);
+    let text = std::str::from_utf8(&bytes[..len]).map_err(serde::de::Error::custom)?;
+    crate::from_str(text)
+}
+
+/// Serialize a text for a Mobile Station 2, padded with zeros to whole
+/// frames of 8 bytes.
+pub fn to_ms2_stream<T>(value: &T) -> crate::ser::Result<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut bytes = crate::to_string(value)?.into_bytes();
+    bytes.resize(bytes.len().next_multiple_of(8), 0);
+    Ok(bytes)
+}
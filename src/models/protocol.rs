@@ -0,0 +1,232 @@
+// Every protocol has its own range of addresses and of uids, the uid of a
+// lokomotive is the start of the uid range plus its address, e.g. 0x4006
+// for the mfx lokomotive with the SID 6.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Lokomotive, LokomotiveFile};
+
+/// The decoder protocol of a `Lokomotive`. Protocols unknown to this crate
+/// are kept as `Other`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Protocol {
+    /// `mm2_prg`, Motorola with programmable address.
+    Mm2Prg,
+    /// `mm2_dil8`, Motorola with the address set by DIP switches.
+    Mm2Dil8,
+    /// `mfx`, the address is the SID assigned by the Central Station.
+    Mfx,
+    /// `dcc`
+    Dcc,
+    /// `sx1`, Selectrix.
+    Sx1,
+    Other(String),
+}
+
+impl Protocol {
+    /// The name in the file.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Protocol::Mm2Prg => "mm2_prg",
+            Protocol::Mm2Dil8 => "mm2_dil8",
+            Protocol::Mfx => "mfx",
+            Protocol::Dcc => "dcc",
+            Protocol::Sx1 => "sx1",
+            Protocol::Other(name) => name,
+        }
+    }
+
+    /// The valid addresses, `None` for other protocols.
+    pub fn adressen(&self) -> Option<RangeInclusive<u16>> {
+        match self {
+            Protocol::Mm2Prg => Some(1..=255),
+            Protocol::Mm2Dil8 => Some(1..=80),
+            Protocol::Mfx => Some(1..=0x3fff),
+            Protocol::Dcc => Some(1..=10239),
+            Protocol::Sx1 => Some(0..=111),
+            Protocol::Other(_) => None,
+        }
+    }
+
+    /// The uid of the lokomotive with the address `adresse`, `None` for
+    /// other protocols and addresses beyond the uid range.
+    ///
+    /// ```
+    /// use serde_cs2::models::Protocol;
+    ///
+    /// assert_eq!(Protocol::Mfx.uid(6), Some(0x4006));
+    /// assert_eq!(Protocol::Dcc.uid(3), Some(0xc003));
+    /// assert_eq!(Protocol::Dcc.uid(0x4000), None);
+    /// ```
+    pub fn uid(&self, adresse: u16) -> Option<u16> {
+        let start: u16 = match self {
+            Protocol::Mm2Prg | Protocol::Mm2Dil8 => 0x0000,
+            Protocol::Sx1 => 0x0800,
+            Protocol::Mfx => 0x4000,
+            Protocol::Dcc => 0xc000,
+            Protocol::Other(_) => return None,
+        };
+        start.checked_add(adresse)
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Infallible> {
+        Ok(match s {
+            "mm2_prg" => Protocol::Mm2Prg,
+            "mm2_dil8" => Protocol::Mm2Dil8,
+            "mfx" => Protocol::Mfx,
+            "dcc" => Protocol::Dcc,
+            "sx1" => Protocol::Sx1,
+            other => Protocol::Other(other.to_owned()),
+        })
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Protocol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Protocol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(name.parse().unwrap_or_else(|never| match never {}))
+    }
+}
+
+/// An inconsistency between protocol, address and uids of a lokomotive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AddressProblem {
+    MissingAdresse,
+    AdresseOutOfRange { adresse: u16, adressen: RangeInclusive<u16> },
+    WrongUid { expected: u16, found: u16 },
+    /// The `sid` of an mfx lokomotive differs from its `adresse`.
+    WrongSid { expected: u16, found: u16 },
+    /// An mfx lokomotive without `mfxuid`.
+    MissingMfxuid,
+    /// A `mfxuid` for another protocol than mfx.
+    UnexpectedMfxuid,
+}
+
+impl fmt::Display for AddressProblem {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressProblem::MissingAdresse => formatter.write_str("missing adresse"),
+            AddressProblem::AdresseOutOfRange { adresse, adressen } => write!(
+                formatter,
+                "adresse {} out of range {}..={}",
+                adresse,
+                adressen.start(),
+                adressen.end()
+            ),
+            AddressProblem::WrongUid { expected, found } => {
+                write!(formatter, "expected uid {:#06x}, found {:#06x}", expected, found)
+            }
+            AddressProblem::WrongSid { expected, found } => {
+                write!(formatter, "expected sid {:#x}, found {:#x}", expected, found)
+            }
+            AddressProblem::MissingMfxuid => formatter.write_str("missing mfxuid"),
+            AddressProblem::UnexpectedMfxuid => formatter.write_str("mfxuid without mfx"),
+        }
+    }
+}
+
+/// An `AddressProblem` of a lokomotive in `LokomotiveFile::lokomotive`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AddressError {
+    /// The number of the lokomotive in `LokomotiveFile::lokomotive`,
+    /// counted from 0. It is not a position in the file, the file may have
+    /// changed since it was read.
+    pub nr: usize,
+    pub name: String,
+    pub problem: AddressProblem,
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "lokomotive {} `{}`: {}", self.nr, self.name, self.problem)
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+impl Lokomotive {
+    /// Check `adresse`, `uid`, `sid` and `mfxuid` against the protocol in
+    /// `typ`. Lokomotives without or with another protocol are not checked.
+    pub fn address_problems(&self) -> Vec<AddressProblem> {
+        let mut problems = vec![];
+        let Some(protocol) = &self.typ else {
+            return problems;
+        };
+        let Some(adressen) = protocol.adressen() else {
+            return problems;
+        };
+
+        match self.adresse {
+            None => problems.push(AddressProblem::MissingAdresse),
+            Some(adresse) if !adressen.contains(&adresse) => {
+                problems.push(AddressProblem::AdresseOutOfRange { adresse, adressen })
+            }
+            Some(adresse) => {
+                let expected = protocol.uid(adresse);
+                if let (Some(expected), Some(found)) = (expected, self.uid) {
+                    if expected != found {
+                        problems.push(AddressProblem::WrongUid { expected, found });
+                    }
+                }
+                if let (Protocol::Mfx, Some(found)) = (protocol, self.sid) {
+                    if found != adresse {
+                        problems.push(AddressProblem::WrongSid { expected: adresse, found });
+                    }
+                }
+            }
+        }
+
+        let mfxuid = self.mfxuid.filter(|mfxuid| *mfxuid != 0);
+        match (protocol, mfxuid) {
+            (Protocol::Mfx, None) => problems.push(AddressProblem::MissingMfxuid),
+            (Protocol::Mfx, Some(_)) | (_, None) => {}
+            (_, Some(_)) => problems.push(AddressProblem::UnexpectedMfxuid),
+        }
+        problems
+    }
+}
+
+impl LokomotiveFile {
+    /// Check the addresses of all lokomotives, see
+    /// `Lokomotive::address_problems`.
+    pub fn validate_adressen(&self) -> Result<(), Vec<AddressError>> {
+        let errors: Vec<_> = self
+            .lokomotive
+            .iter()
+            .enumerate()
+            .flat_map(|(nr, lokomotive)| {
+                lokomotive.address_problems().into_iter().map(move |problem| AddressError {
+                    nr,
+                    name: lokomotive.name.clone(),
+                    problem,
+                })
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
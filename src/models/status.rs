@@ -0,0 +1,219 @@
+// The status files keep the state the Central Station changes at runtime
+// apart from the definitions: `lokstat.cs2` for the lokomotives,
+// `magstat.cs2` for the articles and `fsstat.cs2` for the routes.
+
+use serde::{Deserialize, Serialize};
+
+use super::fields;
+use super::{Fahrstrasse, FahrstrassenFile, Lokomotive, LokomotiveFile, MagnetartikelFile, Stellung, Version};
+use crate::file::Header;
+use crate::map::Map;
+
+/// The entries of `lokstat.cs2`, the body of `File<LokstatFile>` with the
+/// header `[lokomotive]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LokstatFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub lokomotive: Vec<Lokstat>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for LokstatFile {
+    const HEADER: Option<&'static str> = Some("lokomotive");
+}
+
+/// The state of a lokomotive, matched with the definition by name.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Lokstat {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub velocity: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub richtung: Option<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub funktionen: Vec<FunktionStatus>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// The state of a function, a missing `wert` is off.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FunktionStatus {
+    #[serde(with = "fields::number")]
+    pub nr: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub wert: Option<u16>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// The entries of `magstat.cs2`, the body of `File<MagstatFile>` with the
+/// header `[magnetartikel]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MagstatFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub artikel: Vec<ArtikelStatus>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for MagstatFile {
+    const HEADER: Option<&'static str> = Some("magnetartikel");
+}
+
+/// The position of an article, matched with the definition by id.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ArtikelStatus {
+    #[serde(with = "fields::number")]
+    pub id: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stellung: Option<Stellung>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// The entries of `fsstat.cs2`, the body of `File<FsstatFile>` with the
+/// header `[fahrstrassen]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FsstatFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<Version>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub fahrstrasse: Vec<FahrstrasseStatus>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Header for FsstatFile {
+    const HEADER: Option<&'static str> = Some("fahrstrassen");
+}
+
+/// The state of a route, matched with the definition by id.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FahrstrasseStatus {
+    #[serde(with = "fields::number")]
+    pub id: u16,
+    /// 1 while the route is switched.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub aktiv: Option<u8>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+impl Lokstat {
+    /// The number of functions the Central Station writes for every
+    /// lokomotive.
+    pub const FUNKTIONEN: u8 = 32;
+
+    /// A stopped lokomotive with all functions off.
+    pub fn new(name: &str) -> Self {
+        Lokstat {
+            name: name.to_owned(),
+            velocity: Some(0),
+            richtung: Some(0),
+            funktionen: (0..Lokstat::FUNKTIONEN)
+                .map(|nr| FunktionStatus {
+                    nr,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// The value of function `nr`, 0 for functions without status.
+    pub fn funktion(&self, nr: u8) -> u16 {
+        self.funktionen
+            .iter()
+            .find(|funktion| funktion.nr == nr)
+            .and_then(|funktion| funktion.wert)
+            .unwrap_or_default()
+    }
+}
+
+// The status of a definition, functions without value are off.
+impl From<&Lokomotive> for Lokstat {
+    fn from(lokomotive: &Lokomotive) -> Self {
+        let mut lokstat = Lokstat::new(&lokomotive.name);
+        lokstat.velocity = lokomotive.velocity.or(lokstat.velocity);
+        lokstat.richtung = lokomotive.richtung.or(lokstat.richtung);
+        for funktion in lokomotive.funktionen.iter().chain(&lokomotive.funktionen_2) {
+            let wert = funktion.wert.filter(|wert| *wert != 0);
+            match lokstat.funktionen.iter_mut().find(|status| status.nr == funktion.nr) {
+                Some(status) => status.wert = wert,
+                None => lokstat.funktionen.push(FunktionStatus {
+                    nr: funktion.nr,
+                    wert,
+                    ..Default::default()
+                }),
+            }
+        }
+        lokstat
+    }
+}
+
+impl From<&LokomotiveFile> for LokstatFile {
+    fn from(file: &LokomotiveFile) -> Self {
+        LokstatFile {
+            version: Some(Version {
+                minor: Some(3),
+                ..Default::default()
+            }),
+            lokomotive: file.lokomotive.iter().map(Lokstat::from).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl LokomotiveFile {
+    /// Take over speed, direction and function values of `status`. The
+    /// lokomotives are matched by name, status of unknown lokomotives and
+    /// functions is ignored. A function which is off keeps its `wert`
+    /// omitted.
+    pub fn apply_status(&mut self, status: &LokstatFile) {
+        for lokstat in &status.lokomotive {
+            let lokomotive = self.lokomotive.iter_mut().find(|lokomotive| lokomotive.name == lokstat.name);
+            if let Some(lokomotive) = lokomotive {
+                lokomotive.velocity = lokstat.velocity.or(lokomotive.velocity);
+                lokomotive.richtung = lokstat.richtung.or(lokomotive.richtung);
+                for funktion in lokomotive.funktionen.iter_mut().chain(&mut lokomotive.funktionen_2) {
+                    if lokstat.funktionen.iter().any(|status| status.nr == funktion.nr) {
+                        let wert = lokstat.funktion(funktion.nr);
+                        if wert != 0 || funktion.wert.is_some() {
+                            funktion.wert = Some(wert);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl MagnetartikelFile {
+    /// Take over the positions of `status`, matched by id.
+    pub fn apply_status(&mut self, status: &MagstatFile) {
+        for artikel_status in &status.artikel {
+            let artikel = self.artikel.iter_mut().find(|artikel| artikel.id == artikel_status.id);
+            if let Some(artikel) = artikel {
+                artikel.stellung = artikel_status.stellung.or(artikel.stellung);
+            }
+        }
+    }
+}
+
+impl FahrstrassenFile {
+    /// The routes which are switched according to `status`.
+    pub fn aktiv<'a>(&'a self, status: &'a FsstatFile) -> impl Iterator<Item = &'a Fahrstrasse> + 'a {
+        self.fahrstrasse.iter().filter(move |fahrstrasse| {
+            status
+                .fahrstrasse
+                .iter()
+                .any(|status| status.id == fahrstrasse.id && status.aktiv.unwrap_or_default() != 0)
+        })
+    }
+}
@@ -0,0 +1,157 @@
+// The `typ` of a function is the number of its symbol on the Central
+// Station. Bit 7 marks a momentary function which is only on while the
+// key is pressed, e.g. the horn as 10 + 128 = 138.
+//
+// The catalog covers the symbols 1 to 31 of the Central Station 2, the
+// symbols of the Central Station 3 above are kept as `Other`.
+
+use std::hash::{Hash, Hasher};
+
+use super::Funktion;
+
+macro_rules! function_symbol {
+    ($($variant:ident => $number:literal, $de:literal, $en:literal,)*) => {
+        /// The symbol of a `Funktion` without the momentary flag. The known
+        /// symbols are 1 to 31, other numbers are kept as `Other`. An
+        /// `Other` is compared by its number without the momentary flag,
+        /// `Other(10)` is the same symbol as `Horn`.
+        #[derive(Clone, Copy, Debug)]
+        pub enum FunctionSymbol {
+            $(
+                #[doc = concat!($number, ", ", $en, ".")]
+                $variant,
+            )*
+            Other(u16),
+        }
+
+        impl FunctionSymbol {
+            /// The number of the symbol, without the momentary flag.
+            pub fn number(self) -> u16 {
+                match self {
+                    $(FunctionSymbol::$variant => $number,)*
+                    FunctionSymbol::Other(number) => number & !FunctionSymbol::MOMENTARY,
+                }
+            }
+
+            /// The German name as shown by the Central Station.
+            pub fn name_de(self) -> Option<&'static str> {
+                match FunctionSymbol::from(self.number()) {
+                    $(FunctionSymbol::$variant => Some($de),)*
+                    FunctionSymbol::Other(_) => None,
+                }
+            }
+
+            /// The English name.
+            pub fn name_en(self) -> Option<&'static str> {
+                match FunctionSymbol::from(self.number()) {
+                    $(FunctionSymbol::$variant => Some($en),)*
+                    FunctionSymbol::Other(_) => None,
+                }
+            }
+        }
+
+        // The momentary flag is dropped, known numbers are never an `Other`.
+        impl From<u16> for FunctionSymbol {
+            fn from(number: u16) -> Self {
+                match number & !FunctionSymbol::MOMENTARY {
+                    $($number => FunctionSymbol::$variant,)*
+                    number => FunctionSymbol::Other(number),
+                }
+            }
+        }
+    };
+}
+
+function_symbol! {
+    Licht => 1, "Licht", "light",
+    Innenbeleuchtung => 2, "Innenbeleuchtung", "interior light",
+    Ruecklicht => 3, "Rücklicht", "tail light",
+    Fernlicht => 4, "Fernlicht", "high beam",
+    Geraeusch => 5, "Geräusch", "sound",
+    Pantograph => 6, "Pantograph", "pantograph",
+    Rauch => 7, "Rauchgenerator", "smoke generator",
+    Rangiergang => 8, "Rangiergang", "shunting mode",
+    Telex => 9, "Telexkupplung", "telex coupler",
+    Horn => 10, "Horn", "horn",
+    Schaffnerpfiff => 11, "Schaffnerpfiff", "conductor whistle",
+    Pfeife => 12, "Pfeife", "whistle",
+    Glocke => 13, "Glocke", "bell",
+    LinksRechts => 14, "Links/Rechts", "left/right",
+    HebenSenken => 15, "Heben/Senken", "raise/lower",
+    Drehen => 16, "Drehen", "rotate",
+    Kran => 17, "Kran", "crane",
+    AbvAus => 18, "ABV aus", "acceleration delay off",
+    Pumpe => 19, "Pumpe", "pump",
+    Bremsenquietschen => 20, "Bremsenquietschen", "brake squeal",
+    Schaltstufen => 21, "Schaltstufen", "notches",
+    Generator => 22, "Generator", "generator",
+    Betriebsgeraeusch => 23, "Betriebsgeräusch", "operating sound",
+    Motor => 24, "Motor", "engine",
+    Bahnhofsansage => 25, "Bahnhofsansage", "station announcement",
+    KohleSchaufeln => 26, "Kohle schaufeln", "coal shoveling",
+    TuerenSchliessen => 27, "Türen schließen", "doors closing",
+    TuerenOeffnen => 28, "Türen öffnen", "doors opening",
+    Entwaessern => 29, "Entwässern", "blow down",
+    Luefter => 30, "Lüfter", "fan",
+    Fuehrerstand => 31, "Führerstandsbeleuchtung", "cab light",
+}
+
+impl PartialEq for FunctionSymbol {
+    fn eq(&self, other: &Self) -> bool {
+        self.number() == other.number()
+    }
+}
+
+impl Eq for FunctionSymbol {}
+
+impl Hash for FunctionSymbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.number().hash(state);
+    }
+}
+
+impl FunctionSymbol {
+    /// The flag of a momentary function in the raw `typ`.
+    pub const MOMENTARY: u16 = 0x80;
+
+    /// The symbol and whether the function is momentary of a raw `typ`.
+    ///
+    /// ```
+    /// use serde_cs2::models::FunctionSymbol;
+    ///
+    /// assert_eq!(FunctionSymbol::from_raw(138), (FunctionSymbol::Horn, true));
+    /// assert_eq!(FunctionSymbol::Horn.to_raw(false), 10);
+    /// ```
+    pub fn from_raw(typ: u16) -> (Self, bool) {
+        (
+            FunctionSymbol::from(typ & !FunctionSymbol::MOMENTARY),
+            typ & FunctionSymbol::MOMENTARY != 0,
+        )
+    }
+
+    /// The raw `typ` of a permanent or momentary function with the symbol.
+    pub fn to_raw(self, momentary: bool) -> u16 {
+        if momentary {
+            self.number() | FunctionSymbol::MOMENTARY
+        } else {
+            self.number()
+        }
+    }
+}
+
+impl Funktion {
+    /// The symbol decoded from `typ`.
+    pub fn symbol(&self) -> Option<FunctionSymbol> {
+        self.typ.map(|typ| FunctionSymbol::from_raw(typ).0)
+    }
+
+    /// True for a function which is only on while the key is pressed.
+    pub fn is_momentary(&self) -> bool {
+        self.typ.is_some_and(|typ| FunctionSymbol::from_raw(typ).1)
+    }
+
+    /// Set `typ` to the symbol, permanent or momentary.
+    pub fn set_symbol(&mut self, symbol: FunctionSymbol, momentary: bool) {
+        self.typ = Some(symbol.to_raw(momentary));
+    }
+}
@@ -0,0 +1,159 @@
+// A traktion couples several lokomotives of `lokomotive.cs2` which are
+// driven together. Older files list the members by name with `.lok=<name>`,
+// newer ones write a block per member with the uid, the direction and the
+// speed factor of the lokomotive within the traktion.
+
+use std::fmt;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::fields;
+use super::{Lokomotive, LokomotiveFile};
+use crate::map::Map;
+
+/// Lokomotives driven together, a `traktion` block of `lokomotive.cs2`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Traktion {
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub uid: Option<u16>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "fields::list::deserialize")]
+    pub lok: Vec<Member>,
+    #[serde(flatten)]
+    pub unknown: Map,
+}
+
+/// A lokomotive of a `Traktion`, referenced by `uid` or by `name`.
+///
+/// A member with nothing but the name is written as `.lok=<name>`, every
+/// other member as block. A member read as block stays a block.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(remote = "Self")]
+pub struct Member {
+    /// The `uid` of the `Lokomotive`.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::hex_opt")]
+    pub uid: Option<u16>,
+    /// The `name` of the `Lokomotive`, used when the `uid` is missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 1 if the lokomotive runs reversed to the traktion.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub richtung: Option<u8>,
+    /// The speed of the lokomotive in percent of the traktion speed.
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "fields::number_opt")]
+    pub faktor: Option<u16>,
+    #[serde(flatten)]
+    pub unknown: Map,
+    /// Write the member as block even with nothing but the name, set for
+    /// members read as block.
+    #[serde(skip)]
+    pub block: bool,
+}
+
+impl Member {
+    /// A member referenced by name only.
+    pub fn new(name: &str) -> Self {
+        Member {
+            name: Some(name.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// The lokomotive of the member, looked up by `uid` and by `name` if
+    /// the member has no uid.
+    pub fn resolve<'a>(&self, lokomotive: &'a LokomotiveFile) -> Option<&'a Lokomotive> {
+        match (self.uid, &self.name) {
+            (Some(uid), _) => lokomotive.lokomotive.iter().find(|lok| lok.uid == Some(uid)),
+            (None, Some(name)) => lokomotive.lokomotive.iter().find(|lok| lok.name == *name),
+            (None, None) => None,
+        }
+    }
+
+    fn is_name_only(&self) -> bool {
+        self.name.is_some()
+            && self.uid.is_none()
+            && self.richtung.is_none()
+            && self.faktor.is_none()
+            && self.unknown.is_empty()
+    }
+}
+
+impl fmt::Display for Member {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match (self.uid, &self.name) {
+            (Some(uid), _) => write!(formatter, "{:#06x}", uid),
+            (None, Some(name)) => formatter.write_str(name),
+            (None, None) => formatter.write_str("?"),
+        }
+    }
+}
+
+impl Serialize for Member {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.name {
+            Some(name) if !self.block && self.is_name_only() => serializer.serialize_str(name),
+            _ => Member::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Member {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(MemberVisitor)
+    }
+}
+
+struct MemberVisitor;
+
+impl<'de> Visitor<'de> for MemberVisitor {
+    type Value = Member;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a lokomotive name or block")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Member, E> {
+        Ok(Member::new(name))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Member, A::Error> {
+        let mut member = Member::deserialize(de::value::MapAccessDeserializer::new(map))?;
+        member.block = true;
+        Ok(member)
+    }
+}
+
+impl Traktion {
+    /// The members with their lokomotives, `None` for members missing in
+    /// `lokomotive`.
+    pub fn resolve<'a>(
+        &'a self,
+        lokomotive: &'a LokomotiveFile,
+    ) -> impl Iterator<Item = (&'a Member, Option<&'a Lokomotive>)> + 'a {
+        self.lok.iter().map(move |member| (member, member.resolve(lokomotive)))
+    }
+}
+
+impl LokomotiveFile {
+    /// Check that every member of every traktion references a lokomotive
+    /// of the file. The error lists the traktions with their missing
+    /// members.
+    pub fn validate_traktion(&self) -> Result<(), Vec<(&Traktion, &Member)>> {
+        let missing: Vec<_> = self
+            .traktion
+            .iter()
+            .flat_map(|traktion| {
+                traktion
+                    .resolve(self)
+                    .filter(|(_, lokomotive)| lokomotive.is_none())
+                    .map(move |(member, _)| (traktion, member))
+            })
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
+    }
+}
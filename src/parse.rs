@@ -0,0 +1,158 @@
+// The cs2 format is line based. Every line is either empty, a file header
+// like `[lokomotive]`, a block start consisting of a key only or a key value
+// pair separated by '='. The nesting level of a line is the number of '.' in
+// front of the key, any white space in front of the dots is ignored.
+//
+// lokomotive
+//  .name=Lok
+//  .funktionen
+//  ..nr=1
+//
+// This module splits the input into lines and builds a tree of entries out of
+// them. The tree borrows all keys and values from the input so deserializing
+// strings does not need any allocation.
+
+use crate::error::{Error, Result};
+
+// A single classified line of the input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Line<'a> {
+    Blank,
+    Header(&'a str),
+    Entry {
+        level: usize,
+        key: &'a str,
+        value: Option<&'a str>,
+    },
+}
+
+impl<'a> Line<'a> {
+    // Classify a line. The line must not contain the line ending.
+    pub(crate) fn parse(line: &'a str) -> Line<'a> {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let content = line.trim_start();
+        if content.trim_end().is_empty() {
+            return Line::Blank;
+        }
+        if let Some(name) = content
+            .trim_end()
+            .strip_prefix('[')
+            .and_then(|name| name.strip_suffix(']'))
+        {
+            return Line::Header(name);
+        }
+        let level = content.chars().take_while(|c| *c == '.').count();
+        let content = &content[level..];
+        match content.find('=') {
+            Some(pos) => Line::Entry {
+                level,
+                key: &content[..pos],
+                value: Some(&content[pos + 1..]),
+            },
+            None => Line::Entry {
+                level,
+                key: content.trim_end(),
+                value: None,
+            },
+        }
+    }
+}
+
+// The value of an entry is either a scalar, which is everything behind the
+// '=', or a block with nested entries.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Node<'a> {
+    Scalar(&'a str),
+    Block(Vec<Entry<'a>>),
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct Entry<'a> {
+    pub key: &'a str,
+    pub node: Node<'a>,
+}
+
+// A full cs2 document with the optional file header and all entries on the
+// top level.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Tree<'a> {
+    pub header: Option<&'a str>,
+    pub entries: Vec<Entry<'a>>,
+}
+
+impl<'a> Tree<'a> {
+    pub(crate) fn parse(input: &'a str) -> Result<Self> {
+        let mut lines = input
+            .split('\n')
+            .map(Line::parse)
+            .filter(|line| *line != Line::Blank)
+            .peekable();
+
+        let header = match lines.peek() {
+            Some(Line::Header(name)) => {
+                let name = *name;
+                lines.next();
+                Some(name)
+            }
+            _ => None,
+        };
+        let entries = parse_block(&mut lines, 0)?;
+        match lines.next() {
+            None => Ok(Tree { header, entries }),
+            Some(Line::Header(_)) => Err(Error::UnexpectedHeader),
+            Some(_) => Err(Error::WrongLevel),
+        }
+    }
+}
+
+// Collect all entries of one nesting level. The block ends at the first line
+// with a lower level.
+fn parse_block<'a, I>(lines: &mut std::iter::Peekable<I>, level: usize) -> Result<Vec<Entry<'a>>>
+where
+    I: Iterator<Item = Line<'a>>,
+{
+    let mut entries = vec![];
+    while let Some(Line::Entry { level: line_level, key, value }) = lines.peek().copied() {
+        if line_level < level {
+            break;
+        }
+        if line_level > level {
+            return Err(Error::WrongLevel);
+        }
+        lines.next();
+        let node = match value {
+            Some(value) => Node::Scalar(value),
+            None => Node::Block(parse_block(lines, level + 1)?),
+        };
+        entries.push(Entry { key, node });
+    }
+    Ok(entries)
+}
+
+// Byte offset of the line following the last block on the top level with the
+// given key, `None` if there is no such block. Blank lines behind the block
+// are not part of it.
+pub(crate) fn end_of_last_block(input: &str, key: &str) -> Option<usize> {
+    let mut end = None;
+    let mut in_block = false;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        offset += line.len();
+        match Line::parse(line.trim_end_matches('\n')) {
+            Line::Blank => {}
+            Line::Header(_) => in_block = false,
+            Line::Entry { level: 0, key: line_key, .. } => {
+                in_block = line_key == key;
+                if in_block {
+                    end = Some(offset);
+                }
+            }
+            Line::Entry { .. } => {
+                if in_block {
+                    end = Some(offset);
+                }
+            }
+        }
+    }
+    end
+}
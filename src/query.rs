@@ -0,0 +1,239 @@
+// Selecting entries of a document by a path like
+//
+// lokomotive[name="BR 218"].funktionen[nr=3].typ
+//
+// A path consists of keys separated by '.', each key selects the entries
+// with this key in the blocks selected so far, `*` selects all entries.
+// Filters in brackets behind a key narrow the selection down:
+//
+// [name="BR 218"]  blocks with an entry `name` with this value, the quotes
+//                  are optional if the value contains no ']'
+// [mfxuid]         blocks with an entry `mfxuid`
+// [0]              the first of the entries selected in each block
+//
+// The path is evaluated against the entry tree of the parser, so all
+// results borrow from the input.
+
+use crate::error::{Error, Result};
+use crate::parse::{Entry, Node, Tree};
+
+/// A parsed path, see `select` for the syntax.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+    steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Step {
+    // `None` for the wildcard `*`
+    key: Option<String>,
+    filters: Vec<Filter>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Filter {
+    Equals(String, String),
+    Has(String),
+    Index(usize),
+}
+
+/// An entry selected by a `Query`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Match<'a> {
+    key: &'a str,
+    value: Option<&'a str>,
+    position: Position,
+    value_position: Option<Position>,
+}
+
+/// A position in the input. Lines and columns count from 1, the column in
+/// characters.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+/// Select all entries of `input` matching `path`.
+///
+/// ```
+/// let cs2 = "lokomotive\n .name=BR 218\n .funktionen\n ..nr=3\n ..typ=4\n";
+/// let typ = serde_cs2::select(cs2, r#"lokomotive[name="BR 218"].funktionen[nr=3].typ"#).unwrap();
+/// assert_eq!(typ[0].value(), Some("4"));
+/// assert_eq!(typ[0].position().line, 5);
+/// ```
+pub fn select<'a>(input: &'a str, path: &str) -> Result<Vec<Match<'a>>> {
+    path.parse::<Query>()?.select(input)
+}
+
+impl Query {
+    pub fn select<'a>(&self, input: &'a str) -> Result<Vec<Match<'a>>> {
+        let tree = Tree::parse(input)?;
+        let mut selected: Vec<&Entry> = vec![];
+        let mut blocks = vec![tree.entries.as_slice()];
+        for step in &self.steps {
+            selected = vec![];
+            // the filters apply to the entries of each block on their own,
+            // `funktionen[0]` is the first function of every lokomotive
+            for entries in &blocks {
+                let mut entries: Vec<&Entry> = entries
+                    .iter()
+                    .filter(|entry| match &step.key {
+                        Some(key) => entry.key == key,
+                        None => true,
+                    })
+                    .collect();
+                for filter in &step.filters {
+                    entries = match filter {
+                        Filter::Index(index) => entries.get(*index).into_iter().copied().collect(),
+                        _ => entries.into_iter().filter(|entry| filter.matches(entry)).collect(),
+                    };
+                }
+                selected.extend(entries);
+            }
+            blocks = selected
+                .iter()
+                .filter_map(|entry| match &entry.node {
+                    Node::Block(entries) => Some(entries.as_slice()),
+                    Node::Scalar(_) => None,
+                })
+                .collect();
+        }
+        Ok(selected
+            .into_iter()
+            .map(|entry| Match::new(input, entry))
+            .collect())
+    }
+}
+
+impl std::str::FromStr for Query {
+    type Err = Error;
+
+    fn from_str(path: &str) -> Result<Self> {
+        let invalid = |position| Error::InvalidPath {
+            path: path.to_owned(),
+            position,
+        };
+        let mut steps = vec![];
+        let mut rest = path;
+        loop {
+            let position = path.len() - rest.len();
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            let key = match &rest[..end] {
+                "" => return Err(invalid(position)),
+                "*" => None,
+                key => Some(key.to_owned()),
+            };
+            rest = &rest[end..];
+
+            let mut filters = vec![];
+            while let Some(filter) = rest.strip_prefix('[') {
+                let position = path.len() - filter.len();
+                let (filter, remainder) = split_filter(filter).ok_or_else(|| invalid(position))?;
+                filters.push(filter);
+                rest = remainder;
+            }
+            steps.push(Step { key, filters });
+
+            match rest.strip_prefix('.') {
+                Some(remainder) => rest = remainder,
+                None if rest.is_empty() => return Ok(Query { steps }),
+                None => return Err(invalid(path.len() - rest.len())),
+            }
+        }
+    }
+}
+
+// Split the content of a filter behind the '[' off the remaining path.
+fn split_filter(input: &str) -> Option<(Filter, &str)> {
+    let end = input.find([']', '='])?;
+    let key = &input[..end];
+    if key.is_empty() {
+        return None;
+    }
+    if input[end..].starts_with(']') {
+        let filter = match key.parse() {
+            Ok(index) => Filter::Index(index),
+            Err(_) => Filter::Has(key.to_owned()),
+        };
+        return Some((filter, &input[end + 1..]));
+    }
+    let value = &input[end + 1..];
+    let (value, rest) = match value.strip_prefix('"') {
+        Some(quoted) => {
+            let end = quoted.find('"')?;
+            (&quoted[..end], quoted[end + 1..].strip_prefix(']')?)
+        }
+        None => {
+            let end = value.find(']')?;
+            (&value[..end], &value[end + 1..])
+        }
+    };
+    Some((Filter::Equals(key.to_owned(), value.to_owned()), rest))
+}
+
+impl Filter {
+    fn matches(&self, entry: &Entry) -> bool {
+        let entries = match &entry.node {
+            Node::Block(entries) => entries,
+            Node::Scalar(_) => return false,
+        };
+        match self {
+            Filter::Equals(key, value) => entries
+                .iter()
+                .any(|entry| entry.key == key && entry.node == Node::Scalar(value)),
+            Filter::Has(key) => entries.iter().any(|entry| entry.key == key),
+            Filter::Index(_) => true,
+        }
+    }
+}
+
+impl<'a> Match<'a> {
+    fn new(input: &'a str, entry: &Entry<'a>) -> Self {
+        let value = match entry.node {
+            Node::Scalar(value) => Some(value),
+            Node::Block(_) => None,
+        };
+        Match {
+            key: entry.key,
+            value,
+            position: Position::of(input, entry.key),
+            value_position: value.map(|value| Position::of(input, value)),
+        }
+    }
+
+    pub fn key(&self) -> &'a str {
+        self.key
+    }
+
+    /// The text behind the '=', `None` for a block.
+    pub fn value(&self) -> Option<&'a str> {
+        self.value
+    }
+
+    /// The position of the key.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// The position of the value, `None` for a block.
+    pub fn value_position(&self) -> Option<Position> {
+        self.value_position
+    }
+}
+
+impl Position {
+    // All keys and values of the tree are slices of the input, their offset
+    // is the distance to the start of the input.
+    fn of(input: &str, part: &str) -> Self {
+        let offset = part.as_ptr() as usize - input.as_ptr() as usize;
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            offset,
+        }
+    }
+}
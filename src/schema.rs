@@ -0,0 +1,442 @@
+// Inference of a schema from sample documents and generation of Rust types
+// deserializing them.
+//
+// Every key of a block is a field. Its type is the narrowest type all
+// sample values fit into:
+//
+// value            | Rust type
+// -----------------|--------------------------------------------------
+// 5, -1            | smallest integer type, signed if a value is negative
+// 0x4001, 0x5      | integer with `SerHex`, strict if all have full width
+// 0 0 1 0          | array of integers with the length of all samples
+// anything else    | String
+// key only         | struct named like the key
+//
+// A key missing in some instances of its block is an `Option`, a key
+// repeated within a block a `Vec`.
+
+use std::fmt::Write;
+
+use crate::error::{Error, Result};
+use crate::parse::{Entry, Node, Tree};
+
+/// The structure of a cs2 document inferred from samples, used to generate
+/// Rust types for it.
+///
+/// The types are only as general as the samples: a key which is not
+/// repeated in any sample is not a `Vec` and numbers get the smallest type
+/// fitting the sampled values. Pass as many and as different files as
+/// available.
+///
+/// ```
+/// let mut schema = serde_cs2::Schema::new();
+/// schema.add("[lokomotive]\nlokomotive\n .name=Lok\n .uid=0x4001\n .adresse=0x5\n").unwrap();
+/// let code = schema.to_rust("LokomotiveFile");
+/// assert!(code.contains("pub struct Lokomotive {"));
+/// assert!(code.contains("    #[serde(with = \"SerHex::<StrictPfx>\")]\n    pub uid: u16,\n"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schema {
+    samples: usize,
+    header: Option<String>,
+    root: Fields,
+}
+
+// The keys of all instances of a block.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Fields {
+    instances: usize,
+    fields: Vec<Field>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Field {
+    key: String,
+    // The number of block instances containing the key.
+    present: usize,
+    // The highest number of entries with the key in one instance.
+    repeated: usize,
+    scalar: Option<Type>,
+    block: Option<Fields>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Type {
+    Text,
+    Decimal { min: i128, max: i128 },
+    Hex { max: u64, min_digits: usize, max_digits: usize },
+    Array { len: usize, element: Box<Type> },
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Schema::default()
+    }
+
+    /// Add a sample document. All samples must have the same file header
+    /// or none at all.
+    pub fn add(&mut self, input: &str) -> Result<()> {
+        let tree = Tree::parse(input)?;
+        let header = tree.header.map(str::to_owned);
+        if self.samples > 0 && header != self.header {
+            return Err(match (&self.header, header) {
+                (Some(expected), Some(found)) => Error::WrongHeader {
+                    expected: expected.clone(),
+                    found,
+                },
+                (Some(_), None) => Error::ExpectedHeader,
+                (None, _) => Error::UnexpectedHeader,
+            });
+        }
+        self.header = header;
+        self.samples += 1;
+        self.root.add(&tree.entries);
+        Ok(())
+    }
+
+    /// The file header of the samples without the brackets.
+    pub fn header(&self) -> Option<&str> {
+        self.header.as_deref()
+    }
+
+    /// Generate the Rust source of the types with `name` as the type of the
+    /// entries on the top level. If the samples have a file header, it is
+    /// deserialized as `serde_cs2::File<name>` and `name` implements
+    /// `serde_cs2::Header` with it.
+    ///
+    /// The types derive `serde`'s `Deserialize` and `Serialize`,
+    /// hexadecimal numbers need the `serde-hex` crate.
+    pub fn to_rust(&self, name: &str) -> String {
+        let mut generator = Generator {
+            names: vec![name.to_owned()],
+            structs: vec![],
+            hex: vec![],
+        };
+        let doc = match &self.header {
+            Some(header) => format!(
+                "/// The entries of a `[{}]` file, the body of\n/// `serde_cs2::File<{}>`.\n",
+                header, name
+            ),
+            None => String::new(),
+        };
+        generator.generate(name, &doc, &self.root);
+
+        let mut code = String::from("use serde::{Deserialize, Serialize};\n");
+        if !generator.hex.is_empty() {
+            generator.hex.sort_unstable();
+            let _ = writeln!(code, "use serde_hex::{{{}}};", generator.hex.join(", "));
+        }
+        for item in generator.structs {
+            code.push('\n');
+            code.push_str(&item);
+        }
+        if let Some(header) = &self.header {
+            let _ = write!(
+                code,
+                "\nimpl serde_cs2::Header for {} {{\n    const HEADER: Option<&'static str> = Some({:?});\n}}\n",
+                name, header
+            );
+        }
+        code
+    }
+}
+
+impl Fields {
+    fn add(&mut self, entries: &[Entry]) {
+        self.instances += 1;
+        let mut keys: Vec<&str> = vec![];
+        for entry in entries {
+            if !keys.contains(&entry.key) {
+                keys.push(entry.key);
+            }
+        }
+        for key in keys {
+            let index = match self.fields.iter().position(|field| field.key == key) {
+                Some(index) => index,
+                None => {
+                    self.fields.push(Field {
+                        key: key.to_owned(),
+                        present: 0,
+                        repeated: 0,
+                        scalar: None,
+                        block: None,
+                    });
+                    self.fields.len() - 1
+                }
+            };
+            let field = &mut self.fields[index];
+            field.present += 1;
+            let mut count = 0;
+            for entry in entries.iter().filter(|entry| entry.key == key) {
+                count += 1;
+                match &entry.node {
+                    Node::Scalar(value) => {
+                        let value = Type::of(value);
+                        field.scalar = Some(match field.scalar.take() {
+                            Some(scalar) => scalar.join(value),
+                            None => value,
+                        });
+                    }
+                    Node::Block(entries) => {
+                        field.block.get_or_insert_with(Fields::default).add(entries);
+                    }
+                }
+            }
+            field.repeated = field.repeated.max(count);
+        }
+    }
+}
+
+impl Type {
+    fn of(value: &str) -> Type {
+        if value.contains(' ') {
+            let elements: Vec<Type> = value.split(' ').map(Type::scalar).collect();
+            let len = elements.len();
+            return match elements.into_iter().reduce(Type::join) {
+                Some(element @ Type::Decimal { .. }) => Type::Array {
+                    len,
+                    element: Box::new(element),
+                },
+                _ => Type::Text,
+            };
+        }
+        Type::scalar(value)
+    }
+
+    fn scalar(value: &str) -> Type {
+        if let Some(digits) = value.strip_prefix("0x") {
+            if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                if let Ok(max) = u64::from_str_radix(digits, 16) {
+                    return Type::Hex {
+                        max,
+                        min_digits: digits.len(),
+                        max_digits: digits.len(),
+                    };
+                }
+            }
+            return Type::Text;
+        }
+        // Leading zeros and a plus sign would get lost on the way back.
+        let digits = value.strip_prefix('-').unwrap_or(value);
+        let canonical = digits == "0" || !digits.starts_with('0');
+        if canonical && !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+            if let Ok(number) = value.parse::<i128>() {
+                if i128::from(i64::MIN) <= number && number <= i128::from(u64::MAX) {
+                    return Type::Decimal {
+                        min: number,
+                        max: number,
+                    };
+                }
+            }
+        }
+        Type::Text
+    }
+
+    fn join(self, other: Type) -> Type {
+        match (self, other) {
+            (Type::Decimal { min, max }, Type::Decimal { min: other_min, max: other_max }) => {
+                Type::Decimal {
+                    min: min.min(other_min),
+                    max: max.max(other_max),
+                }
+            }
+            (
+                Type::Hex { max, min_digits, max_digits },
+                Type::Hex { max: other_max, min_digits: other_min_digits, max_digits: other_max_digits },
+            ) => Type::Hex {
+                max: max.max(other_max),
+                min_digits: min_digits.min(other_min_digits),
+                max_digits: max_digits.max(other_max_digits),
+            },
+            (Type::Array { len, element }, Type::Array { len: other_len, element: other })
+                if len == other_len =>
+            {
+                match element.join(*other) {
+                    element @ Type::Decimal { .. } => Type::Array {
+                        len,
+                        element: Box::new(element),
+                    },
+                    _ => Type::Text,
+                }
+            }
+            _ => Type::Text,
+        }
+    }
+}
+
+struct Generator {
+    // The names of all structs, the first one is the top level.
+    names: Vec<String>,
+    // The generated structs, nested ones in front of their parent.
+    structs: Vec<String>,
+    // The items of `serde_hex` in use.
+    hex: Vec<&'static str>,
+}
+
+impl Generator {
+    fn generate(&mut self, name: &str, doc: &str, fields: &Fields) {
+        let mut code = String::from(doc);
+        code.push_str("#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]\n");
+        let _ = writeln!(code, "pub struct {} {{", name);
+        for field in &fields.fields {
+            let ident = field_ident(&field.key);
+            let mut attributes = vec![];
+            if ident.trim_start_matches("r#") != field.key {
+                attributes.push(format!("rename = \"{}\"", field.key));
+            }
+            let repeated = field.repeated > 1;
+            let optional = !repeated && field.present < fields.instances;
+            let mut with = vec![];
+            let mut ty = match &field.block {
+                Some(block) => {
+                    let name = self.struct_name(name, &field.key);
+                    self.generate(&name, "", block);
+                    name
+                }
+                None => {
+                    let scalar = field.scalar.as_ref().unwrap_or(&Type::Text);
+                    self.scalar(scalar, repeated, optional, &mut with)
+                }
+            };
+            if repeated {
+                ty = format!("Vec<{}>", ty);
+                attributes.push("default, skip_serializing_if = \"Vec::is_empty\"".to_owned());
+            } else if optional {
+                ty = format!("Option<{}>", ty);
+                attributes.push("default, skip_serializing_if = \"Option::is_none\"".to_owned());
+            }
+            attributes.append(&mut with);
+            if !attributes.is_empty() {
+                let _ = writeln!(code, "    #[serde({})]", attributes.join(", "));
+            }
+            let _ = writeln!(code, "    pub {}: {},", ident, ty);
+        }
+        code.push_str("}\n");
+        self.structs.push(code);
+    }
+
+    // The Rust type of a scalar. `SerHex` doesn't support sequences, so
+    // repeated hexadecimal values stay strings.
+    fn scalar(&mut self, ty: &Type, repeated: bool, optional: bool, attributes: &mut Vec<String>) -> String {
+        match ty {
+            Type::Text => "String".to_owned(),
+            Type::Decimal { min, max } => integer(*min, *max).to_owned(),
+            Type::Hex { .. } if repeated => "String".to_owned(),
+            Type::Hex { max, min_digits, max_digits } => {
+                let bytes = match *max_digits {
+                    0..=2 => 1,
+                    3..=4 => 2,
+                    5..=8 => 4,
+                    _ => 8,
+                };
+                let bytes = bytes.max(match *max {
+                    0..=0xff => 1,
+                    0x100..=0xffff => 2,
+                    0x1_0000..=0xffff_ffff => 4,
+                    _ => 8,
+                });
+                let strict = *min_digits == bytes * 2 && *max_digits == bytes * 2;
+                let (with, padding) = match (optional, strict) {
+                    (false, true) => ("SerHex", "StrictPfx"),
+                    (false, false) => ("SerHex", "CompactPfx"),
+                    (true, true) => ("SerHexOpt", "StrictPfx"),
+                    (true, false) => ("SerHexOpt", "CompactPfx"),
+                };
+                for item in [with, padding] {
+                    if !self.hex.contains(&item) {
+                        self.hex.push(item);
+                    }
+                }
+                attributes.push(format!("with = \"{}::<{}>\"", with, padding));
+                format!("u{}", bytes * 8)
+            }
+            Type::Array { len, element } => {
+                format!("[{}; {}]", self.scalar(element, false, false, attributes), len)
+            }
+        }
+    }
+
+    // The struct of a nested block is named like its key, prefixed with the
+    // parent if that name is taken already.
+    fn struct_name(&mut self, parent: &str, key: &str) -> String {
+        let mut name = type_ident(key);
+        if self.names.contains(&name) {
+            name = format!("{}{}", parent, name);
+        }
+        let base = name.clone();
+        let mut index = 2;
+        while self.names.contains(&name) {
+            name = format!("{}{}", base, index);
+            index += 1;
+        }
+        self.names.push(name.clone());
+        name
+    }
+}
+
+fn integer(min: i128, max: i128) -> &'static str {
+    let fits = |low: i128, high: i128| low <= min && max <= high;
+    if min < 0 {
+        if fits(i8::MIN.into(), i8::MAX.into()) {
+            "i8"
+        } else if fits(i16::MIN.into(), i16::MAX.into()) {
+            "i16"
+        } else if fits(i32::MIN.into(), i32::MAX.into()) {
+            "i32"
+        } else {
+            "i64"
+        }
+    } else if max <= u8::MAX.into() {
+        "u8"
+    } else if max <= u16::MAX.into() {
+        "u16"
+    } else if max <= u32::MAX.into() {
+        "u32"
+    } else {
+        "u64"
+    }
+}
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+    "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+    "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized",
+    "use", "virtual", "where", "while", "yield",
+];
+
+// The snake case field name of a key, e.g. `mfx_adr` for `mfxAdr`.
+fn field_ident(key: &str) -> String {
+    let mut ident = String::new();
+    let mut previous = '_';
+    for c in key.chars() {
+        if c.is_ascii_uppercase() && (previous.is_ascii_lowercase() || previous.is_ascii_digit()) {
+            ident.push('_');
+        }
+        ident.push(if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' });
+        previous = c;
+    }
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    match ident.as_str() {
+        "crate" | "self" | "super" | "_" => ident + "_",
+        keyword if KEYWORDS.contains(&keyword) => format!("r#{}", ident),
+        _ => ident,
+    }
+}
+
+// The camel case type name of a key, e.g. `Funktionen2` for `funktionen_2`.
+fn type_ident(key: &str) -> String {
+    let mut ident: String = key
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .flat_map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|c| c.to_ascii_uppercase()).into_iter().chain(chars)
+        })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, 'T');
+    }
+    ident
+}
@@ -1,21 +1,21 @@
-//use std::error::Error;
+use std::borrow::Cow;
 use std::io;
 use std::fmt;
 use serde::ser::Impossible;
 use serde::{ser, Serialize};
-//use write::Writer;
-//use parse::Item;
+
+use crate::file;
+use crate::parse;
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 #[derive(Copy, Clone, Debug)]
 pub enum UnsupportedType {
-    Map,
-    NewtypeStruct,
     NewtypeVariant,
+    StructVariant,
+    TupleVariant,
     Unit,
     UnitStruct,
-    UnitVariant,
 }
 
 #[derive(Debug)]
@@ -56,14 +56,115 @@ impl ser::Error for Error {
     }
 }
 
+// The serializer writes the output line by line. Instead of looking at the
+// output written so far, it keeps track of the current position in the line.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum State {
+    // at the start of a new line
+    #[default]
+    Start,
+    // the key of the current field is not written yet, it is written
+    // together with the first value. This way `None` and empty sequences
+    // don't produce any output.
+    Key,
+    // inside a line after the '='
+    Value,
+    // inside an array value, `first` is set until the first element is written
+    Array { first: bool },
+    // the fields of a `File` are serialized
+    File,
+    // the next struct is the body of a `File`
+    Body,
+}
+
+#[derive(Default)]
 pub struct Serializer {
     level: usize,
+    state: State,
+    // the pending key for `State::Key`, struct fields are borrowed and only
+    // the keys of maps are owned
+    key: Cow<'static, str>,
+    // name of the block on the top level instead of the struct name
+    name: Option<String>,
+    // unsigned integers of these keys are written as hexadecimal numbers,
+    // the way back of `Mapping::hex_keys` of the JSON and YAML bridge
+    hex_keys: Vec<String>,
     output: String,
 }
 
-impl Default for Serializer{
-    fn default() -> Self {
-        Self { level: Default::default(), output: Default::default() }
+// A long run of dots, each indention level adds one. Lines are indented by
+// copying a slice of it instead of pushing the dots one by one.
+const DOTS: &str = "................................";
+
+impl Serializer {
+    // Indent a new line by the current level
+    fn indent(&mut self) {
+        if self.level > 0 {
+            self.output.push(' ');
+            let mut level = self.level;
+            while level > DOTS.len() {
+                self.output.push_str(DOTS);
+                level -= DOTS.len();
+            }
+            self.output.push_str(&DOTS[..level]);
+        }
+    }
+
+    // Write the pending key in front of a value
+    fn begin_value(&mut self) -> Result<()> {
+        match self.state {
+            State::Key => {
+                self.indent();
+                self.output.push_str(&self.key);
+                self.output.push('=');
+                self.state = State::Value;
+            }
+            State::Start => self.state = State::Value,
+            State::Value | State::Array { .. } => {}
+            State::File | State::Body => {
+                return Err(Error::Custom("the body of a file must be a struct".to_owned()));
+            }
+        }
+        Ok(())
+    }
+
+    // Terminate the current line after a value
+    fn end_value(&mut self) {
+        match self.state {
+            State::Value => {
+                self.output.push('\n');
+                self.state = State::Start;
+            }
+            // nothing was written for this field
+            State::Key => self.state = State::Start,
+            _ => {}
+        }
+    }
+
+    // Format an integer without allocating, the digits are written back to
+    // front into a buffer on the stack.
+    fn write_integer(&mut self, negative: bool, mut v: u64) -> Result<()> {
+        self.begin_value()?;
+        if !negative && self.hex_keys.iter().any(|key| *key == self.key) {
+            use std::fmt::Write;
+            return write!(self.output, "{:#x}", v).map_err(|e| Error::Custom(e.to_string()));
+        }
+        let mut buffer = [0u8; 20];
+        let mut pos = buffer.len();
+        loop {
+            pos -= 1;
+            buffer[pos] = b'0' + (v % 10) as u8;
+            v /= 10;
+            if v == 0 {
+                break;
+            }
+        }
+        if negative {
+            self.output.push('-');
+        }
+        // only ASCII digits were written to the buffer
+        self.output.push_str(std::str::from_utf8(&buffer[pos..]).unwrap_or_default());
+        Ok(())
     }
 }
 
@@ -80,14 +181,14 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     type Error = Error;
 
     // Associated types for keeping track of additional state while serializing
-    // compound data structures like sequences and maps. In this case no
-    // additional state is required beyond what is already stored in the
-    // Serializer struct.
-    type SerializeSeq = Self;
+    // compound data structures like sequences and maps. Sequences have to
+    // remember the key to repeat it for every element, everything else is
+    // stored in the Serializer struct.
+    type SerializeSeq = Seq<'a>;
     type SerializeTuple = Self;
-    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleStruct = Self;
     type SerializeTupleVariant = Impossible<(), Error>;
-    type SerializeMap = Impossible<(), Error>;
+    type SerializeMap = Block<'a>;
     type SerializeStruct = Self;
     type SerializeStructVariant = Impossible<(), Error>;
 
@@ -95,7 +196,8 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     // of the primitive types of the data model and map it to cs2 by appending
     // into the output string.
     fn serialize_bool(self, v: bool) -> Result<()> {
-        self.output += if v { "1" } else { "0" };
+        self.begin_value()?;
+        self.output.push(if v { '1' } else { '0' });
         Ok(())
     }
 
@@ -115,11 +217,8 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         self.serialize_i64(i64::from(v))
     }
 
-    // Not particularly efficient but this is example code anyway. A more
-    // performant approach would be to use the `itoa` crate.
     fn serialize_i64(self, v: i64) -> Result<()> {
-        self.output += &v.to_string();
-        Ok(())
+        self.write_integer(v < 0, v.unsigned_abs())
     }
 
     fn serialize_u8(self, v: u8) -> Result<()> {
@@ -135,43 +234,43 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     }
 
     fn serialize_u64(self, v: u64) -> Result<()> {
-        self.output += &v.to_string();
-        Ok(())
+        self.write_integer(false, v)
     }
 
     fn serialize_f32(self, v: f32) -> Result<()> {
         self.serialize_f64(f64::from(v))
     }
 
+    // `fmt::Write` formats directly into the output without allocating.
     fn serialize_f64(self, v: f64) -> Result<()> {
-        self.output += &v.to_string();
-        Ok(())
+        use std::fmt::Write;
+        self.begin_value()?;
+        write!(self.output, "{}", v).map_err(|e| Error::Custom(e.to_string()))
     }
 
     // Serialize a char as a single-character string. Other formats may
     // represent this differently.
     fn serialize_char(self, v: char) -> Result<()> {
-        self.serialize_str(&v.to_string())
+        self.begin_value()?;
+        self.output.push(v);
+        Ok(())
     }
 
     // This only works for strings that don't require escape sequences but you
     // get the idea.
     fn serialize_str(self, v: &str) -> Result<()> {
-        self.output += v;
+        self.begin_value()?;
+        self.output.push_str(v);
         Ok(())
     }
 
-    // Serialize a byte array as an array of bytes. Could also use a base64
-    // string here. Binary formats will typically represent byte arrays more
-    // compactly.
-    // This is used for hexadecimal values
+    // The `Deserializer` hands out the raw text of a value as bytes, which is
+    // used for hexadecimal values. The same representation is used here.
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
-        use serde::ser::SerializeSeq;
-        let mut seq = self.serialize_seq(Some(v.len()))?;
-        for byte in v {
-            seq.serialize_element(byte)?;
+        match std::str::from_utf8(v) {
+            Ok(s) => self.serialize_str(s),
+            Err(e) => Err(Error::Custom(e.to_string())),
         }
-        seq.end()
     }
 
     // An absent optional is is empty
@@ -201,26 +300,27 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         Err(Error::UnsupportedType(UnsupportedType::UnitStruct))
     }
 
-    // Unit variant is not used in cs2
+    // A unit variant is written as the name of the variant, like the
+    // deserializer reads it.
     fn serialize_unit_variant(
         self,
         _name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
     ) -> Result<()> {
-        Err(Error::UnsupportedType(UnsupportedType::UnitVariant))
+        self.serialize_str(variant)
     }
 
-    // Tuple newtype struct is not used in cs2
+    // A newtype struct is written as the value it contains.
     fn serialize_newtype_struct<T>(
         self,
         _name: &'static str,
-        _value: &T,
+        value: &T,
     ) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        Err(Error::UnsupportedType(UnsupportedType::NewtypeStruct))
+        value.serialize(self)
     }
 
     // Tuple newtype variant is not used in cs2
@@ -239,13 +339,19 @@ impl<'a> ser::Serializer for &'a mut Serializer {
 
     // Now we get to the serialization of compound types.
     //
-    // The start of the sequence, each value, and the end are three separate
-    // method calls. This one is responsible only for serializing the start,
-    // which in cs2 is the struct name.
+    // Sequences are collections of blocks or values with the same key. Every
+    // element repeats the key of the field in a new line.
     //
     // The length of the sequence is not known ahead of time.
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
-        Ok(self)
+        let key = match self.state {
+            State::Key => Some(self.key.clone()),
+            State::Start => None,
+            _ => {
+                return Err(Error::Custom("sequences cannot be nested into a value".to_owned()));
+            }
+        };
+        Ok(Seq { ser: self, key })
     }
 
     // Tuples are arrays in the cs2 format. The values are blank separated in
@@ -254,16 +360,22 @@ impl<'a> ser::Serializer for &'a mut Serializer {
     // the length, since tuple  means that the corresponding `Deserialize implementation
     // will know the length without needing to look at the serialized data.
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        if let State::Array { .. } = self.state {
+            return Err(Error::Custom("arrays cannot be nested".to_owned()));
+        }
+        self.begin_value()?;
+        self.state = State::Array { first: true };
         Ok(self)
     }
 
-    // Tuple structs are not used in cs2
+    // Tuple structs are written like tuples, their name is not part of the
+    // value.
     fn serialize_tuple_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
-        unimplemented!()
+        self.serialize_tuple(len)
     }
 
     // Tuple variants are not used in cs2 format.
@@ -274,16 +386,45 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        unimplemented!()
+        Err(Error::UnsupportedType(UnsupportedType::TupleVariant))
     }
 
-    // Maps are not used in cs2 format
+    // Maps are blocks with arbitrary keys. They have no name of their own, a
+    // map on the top level contains the entries of the whole document.
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
-        unimplemented!()
+        let nested = match self.state {
+            State::Start if self.name.is_none() => false,
+            // a block appended to a file is named like a struct
+            State::Start => {
+                self.indent();
+                let block = self.name.take();
+                self.output.push_str(block.as_deref().unwrap_or_default());
+                true
+            }
+            State::Key => {
+                self.indent();
+                self.output.push_str(&self.key);
+                true
+            }
+            State::Body => {
+                self.state = State::Start;
+                false
+            }
+            State::Value | State::Array { .. } | State::File => {
+                return Err(Error::Custom("maps cannot be nested into a value".to_owned()));
+            }
+        };
+        if nested {
+            self.output.push('\n');
+            self.state = State::Start;
+            self.level += 1;
+        }
+        Ok(Block { ser: self, nested })
     }
 
     // Structs in cs2 start with just the struct name in one line, followed
-    // by all fields in separate lines.
+    // by all fields in separate lines. Only the struct on the top level uses
+    // its own name, nested structs are named by the field key.
     // each struct starts a new '.' indention level to be able to map the
     // fields to the correct struct.
     // Each field has name, separator '=', value, '\n'
@@ -292,31 +433,36 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct> {
-        if self.output.ends_with("=") {
-            //remove the = at the end since this is a nested struct name
-            let _ = self.output.pop();
-        }
-        if !self.output.ends_with(name) {
-            if self.level > 0 {
-                self.output += "\n ";
-                for _ in 0..self.level {
-                    self.output += ".";
-                }
-            } else if !self.output.is_empty() && !self.output.ends_with("\n") {
-                self.output += "\n";
+        match self.state {
+            // a file writes the header and the body on the top level
+            State::Start if name == file::NAME && self.level == 0 => {
+                self.state = State::File;
+                return Ok(self);
             }
-            if !(self.level == 0 && name.starts_with("[") && name.ends_with("]")) {
-                self.level +=1;
+            // only the struct on the top level is named by its own name
+            State::Start => {
+                self.indent();
+                let block = self.name.take();
+                self.output.push_str(block.as_deref().unwrap_or(name));
             }
-            self.output += name;
-        } else {
-            if !self.output.is_empty() && !self.output.ends_with("\n") {
-                self.output += "\n";
+            // a nested struct is named by the key of the field containing it
+            State::Key => {
+                self.indent();
+                self.output.push_str(&self.key);
+            }
+            // the body of a file has no name and does not start a new
+            // indention level
+            State::Body => {
+                self.state = State::Start;
+                return Ok(self);
+            }
+            State::Value | State::Array { .. } | State::File => {
+                return Err(Error::Custom("structs cannot be nested into a value".to_owned()));
             }
-            // we wrote the own name already as part of the field name
-            // increase only indention level
-            self.level +=1;
         }
+        self.output.push('\n');
+        self.state = State::Start;
+        self.level += 1;
         Ok(self)
     }
 
@@ -328,29 +474,42 @@ impl<'a> ser::Serializer for &'a mut Serializer {
         _variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        unimplemented!()
+        Err(Error::UnsupportedType(UnsupportedType::StructVariant))
     }
 }
 
-// The following 7 impls deal with the serialization of compound types like
+// The following 8 impls deal with the serialization of compound types like
 // sequences and maps. Serialization of such types is begun by a Serializer
 // method and followed by zero or more calls to serialize individual elements of
 // the compound type and one call to end the compound type.
 //
+// Sequences remember the key of the field they belong to, which is `None` for
+// a sequence on the top level.
+pub struct Seq<'a> {
+    ser: &'a mut Serializer,
+    key: Option<Cow<'static, str>>,
+}
+
 // This impl is SerializeSeq so these methods are called after `serialize_seq`
 // is called on the Serializer.
-impl<'a> ser::SerializeSeq for &'a mut Serializer {
+impl ser::SerializeSeq for Seq<'_> {
     // Must match the `Ok` type of the serializer.
     type Ok = ();
     // Must match the `Error` type of the serializer.
     type Error = Error;
 
-    // Serialize a single element of the sequence.
+    // Serialize a single element of the sequence in its own line.
     fn serialize_element<T>(&mut self, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        value.serialize(&mut **self)
+        if let Some(key) = &self.key {
+            self.ser.key = key.clone();
+            self.ser.state = State::Key;
+        }
+        value.serialize(&mut *self.ser)?;
+        self.ser.end_value();
+        Ok(())
     }
 
     // Close the sequence.
@@ -359,8 +518,54 @@ impl<'a> ser::SerializeSeq for &'a mut Serializer {
     }
 }
 
+// Maps write their entries like the fields of a struct. A key in brackets on
+// the top level is the file header, its value are the entries of the file.
+pub struct Block<'a> {
+    ser: &'a mut Serializer,
+    nested: bool,
+}
+
+impl ser::SerializeMap for Block<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.ser.key = Cow::Owned(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let ser = &mut *self.ser;
+        if !self.nested && ser.level == 0 && ser.key.starts_with('[') && ser.key.ends_with(']') {
+            ser.output.push_str(&ser.key);
+            ser.output.push('\n');
+            ser.state = State::Body;
+            value.serialize(&mut *ser)?;
+            ser.state = State::Start;
+            return Ok(());
+        }
+        ser.state = State::Key;
+        value.serialize(&mut *ser)?;
+        ser.end_value();
+        Ok(())
+    }
+
+    fn end(self) -> Result<()> {
+        if self.nested {
+            self.ser.level -= 1;
+        }
+        Ok(())
+    }
+}
+
 // Same thing but for tuples.
-impl<'a> ser::SerializeTuple for &'a mut Serializer {
+impl ser::SerializeTuple for &mut Serializer {
     type Ok = ();
     type Error = Error;
 
@@ -368,21 +573,39 @@ impl<'a> ser::SerializeTuple for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with('=') {
+        if self.state == (State::Array { first: false }) {
             // array separator
-            self.output += " ";
+            self.output.push(' ');
         }
+        self.state = State::Array { first: false };
         value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<()> {
+        self.state = State::Value;
         Ok(())
     }
 }
 
+impl ser::SerializeTupleStruct for &mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<()> {
+        ser::SerializeTuple::end(self)
+    }
+}
+
 // Structs are newline separated fields indented by '.'
 // values are separated by '='
-impl<'a> ser::SerializeStruct for &'a mut Serializer {
+impl ser::SerializeStruct for &mut Serializer {
     type Ok = ();
     type Error = Error;
 
@@ -390,20 +613,14 @@ impl<'a> ser::SerializeStruct for &'a mut Serializer {
     where
         T: ?Sized + Serialize,
     {
-        if !self.output.ends_with("\n") {
-            self.output += "\n";
+        if self.state == State::File {
+            return self.serialize_file_field(key, value);
         }
-
-        // indent based on the current level
-        if self.level > 0 {
-            self.output += " ";
-            for _ in 0..self.level {
-                self.output += ".";
-            }
-        }
-        key.serialize(&mut **self)?;
-        self.output += "=";
-        value.serialize(&mut **self)
+        self.key = Cow::Borrowed(key);
+        self.state = State::Key;
+        value.serialize(&mut **self)?;
+        self.end_value();
+        Ok(())
     }
 
     fn end(self) -> Result<()> {
@@ -413,11 +630,210 @@ impl<'a> ser::SerializeStruct for &'a mut Serializer {
     }
 }
 
+impl Serializer {
+    // The header of a file is written in brackets in the first line,
+    // followed by the fields of the body on the top level.
+    fn serialize_file_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if key == file::HEADER {
+            self.output.push('[');
+            self.state = State::Value;
+            value.serialize(&mut *self)?;
+            self.output.push_str("]\n");
+            self.state = State::File;
+        } else {
+            self.state = State::Body;
+            value.serialize(&mut *self)?;
+            self.state = State::Start;
+        }
+        Ok(())
+    }
+}
+
+// Keys of maps are written as they are. Only strings and types which are
+// written like strings are accepted.
+pub(crate) struct KeySerializer;
+
+impl KeySerializer {
+    fn unsupported<T>(self) -> Result<T> {
+        Err(Error::Custom("keys of a map must be strings".to_owned()))
+    }
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_bool(self, _v: bool) -> Result<String> {
+        self.unsupported()
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String> {
+        self.unsupported()
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String> {
+        self.unsupported()
+    }
+
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(v.to_owned())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String> {
+        self.unsupported()
+    }
+
+    fn serialize_none(self) -> Result<String> {
+        self.unsupported()
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.unsupported()
+    }
+
+    fn serialize_unit(self) -> Result<String> {
+        self.unsupported()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        self.unsupported()
+    }
+
+    // Enums like the field identifiers of a struct are written by their name
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Ok(variant.to_owned())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.unsupported()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        self.unsupported()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        self.unsupported()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.unsupported()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        self.unsupported()
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        self.unsupported()
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct> {
+        self.unsupported()
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        self.unsupported()
+    }
+}
+
 // By convention, the public API of a Serde serializer is one or more `to_abc`
 // functions such as `to_string`, `to_bytes`, or `to_writer` depending on what
 // Rust types the serializer is able to produce as output.
 //
-// This basic serializer supports only `to_string`.
+// This serializer supports `to_string` and `to_writer`. In addition single
+// blocks can be appended to an existing file without touching the rest of it.
 pub fn to_string<T>(value: &T) -> Result<String>
 where
     T: ?Sized + Serialize,
@@ -425,8 +841,85 @@ where
     let mut serializer = Serializer::default();
 
     value.serialize(&mut serializer)?;
-    if !serializer.output.is_empty() && !serializer.output.ends_with("\n") {
-        serializer.output.push('\n');
+    Ok(serializer.finish())
+}
+
+pub fn to_writer<W, T>(mut writer: W, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: ?Sized + Serialize,
+{
+    writer.write_all(to_string(value)?.as_bytes())?;
+    Ok(())
+}
+
+/// Append `value` as block named `key` on the top level to the end of a
+/// cs2 file, e.g. a new `lokomotive` to an opened `lokomotive.cs2`.
+///
+/// The writer has to be positioned at the start of a line.
+pub fn append_to_writer<W, T>(mut writer: W, key: &str, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: ?Sized + Serialize,
+{
+    writer.write_all(block_to_string(key, value)?.as_bytes())?;
+    Ok(())
+}
+
+/// Insert `value` as block named `key` on the top level of the cs2 text
+/// `input`. The block is inserted after the last block with the same key or
+/// appended to the end if there is none. The rest of `input` stays unchanged,
+/// including its line endings.
+pub fn insert_into_string<T>(input: &mut String, key: &str, value: &T) -> Result<()>
+where
+    T: ?Sized + Serialize,
+{
+    let mut block = block_to_string(key, value)?;
+    if input.contains("\r\n") {
+        block = block.replace('\n', "\r\n");
+    }
+    match parse::end_of_last_block(input, key) {
+        Some(offset) if offset < input.len() => input.insert_str(offset, &block),
+        _ => {
+            if !input.is_empty() && !input.ends_with('\n') {
+                input.push_str(if block.ends_with("\r\n") { "\r\n" } else { "\n" });
+            }
+            input.push_str(&block);
+        }
+    }
+    Ok(())
+}
+
+// Serialize a struct as block on the top level named by `key` instead of the
+// struct name.
+fn block_to_string<T>(key: &str, value: &T) -> Result<String>
+where
+    T: ?Sized + Serialize,
+{
+    let mut serializer = Serializer {
+        name: Some(key.to_owned()),
+        ..Default::default()
+    };
+
+    value.serialize(&mut serializer)?;
+    if serializer.name.is_some() {
+        return Err(Error::Custom(format!("{} is not a block", key)));
+    }
+    Ok(serializer.finish())
+}
+
+impl Serializer {
+    #[cfg(any(feature = "json", feature = "yaml"))]
+    pub(crate) fn with_hex_keys(hex_keys: Vec<String>) -> Self {
+        Serializer {
+            hex_keys,
+            ..Default::default()
+        }
+    }
+
+    // Every line ends with a newline, including the last one.
+    pub(crate) fn finish(mut self) -> String {
+        self.end_value();
+        self.output
     }
-    Ok(serializer.output)
 }
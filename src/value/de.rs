@@ -0,0 +1,471 @@
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+
+use super::Value;
+use crate::de::{forward_to_scalar, ValueDeserializer};
+use crate::error::{Error, Result};
+use crate::file::{self, HeaderDeserializer};
+
+// A `Value` is deserialized like the text it represents. Scalars are parsed
+// by the same `ValueDeserializer` as the text, blocks are accessed by key with
+// repeated keys grouped together.
+impl<'de> de::Deserializer<'de> for &'de Value {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::String(s) => visitor.visit_borrowed_str(s),
+            Value::Array(values) => visitor.visit_seq(ArraySeq(values.iter())),
+            Value::Block(entries) => visitor.visit_map(EntryAccess::new(entries)),
+            Value::List(values) => visitor.visit_seq(ListSeq(values.iter())),
+        }
+    }
+
+    forward_to_scalar! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_identifier
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // A single block is a sequence with one element, a string is parsed as
+    // blank separated array like in the text.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::String(s) => {
+                let mut de = ValueDeserializer::new(s);
+                let value = de::Deserializer::deserialize_seq(&mut de, visitor)?;
+                de.end()?;
+                Ok(value)
+            }
+            Value::Array(values) => visitor.visit_seq(ArraySeq(values.iter())),
+            Value::Block(_) => visitor.visit_seq(ListSeq(std::slice::from_ref(self).iter())),
+            Value::List(values) => visitor.visit_seq(ListSeq(values.iter())),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Block(entries) => visitor.visit_map(BlockAccess::new(entries)),
+            _ => Err(Error::ExpectedNewline),
+        }
+    }
+
+    // Blocks are not named in a `Value`, the name of the struct is not
+    // checked. A `File` is a block with the header in brackets as only key.
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name != file::NAME {
+            return self.deserialize_map(visitor);
+        }
+        let entry = match self.as_block() {
+            Some([entry]) => entry,
+            _ => return Err(Error::ExpectedHeader),
+        };
+        match entry.0.strip_prefix('[').and_then(|key| key.strip_suffix(']')) {
+            Some(header) => visitor.visit_map(FileAccess {
+                header,
+                body: &entry.1,
+                field: 0,
+            }),
+            None => Err(Error::ExpectedHeader),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let mut de = self.scalar()?;
+        let value = de::Deserializer::deserialize_enum(&mut de, name, variants, visitor)?;
+        de.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+// Scalars are strings only, repeated entries or blocks can't be parsed as
+// a single value.
+impl Value {
+    fn scalar(&self) -> Result<ValueDeserializer<'_>> {
+        match self {
+            Value::String(s) => Ok(ValueDeserializer::new(s)),
+            Value::List(_) => Err(Error::DuplicateKey),
+            _ => Err(Error::ExpectedValueSeperator),
+        }
+    }
+}
+
+// All values of a block with the same key, see `GroupDeserializer` of the
+// text deserializer. A `List` counts as its elements.
+struct Group<'de> {
+    values: Vec<&'de Value>,
+}
+
+impl<'de> Group<'de> {
+    fn single(&self) -> Result<&'de Value> {
+        match self.values.as_slice() {
+            [value] => Ok(value),
+            _ => Err(Error::DuplicateKey),
+        }
+    }
+
+    fn scalar(&self) -> Result<ValueDeserializer<'de>> {
+        self.single()?.scalar()
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &'de Value> + '_ {
+        self.values.iter().flat_map(|value| match value {
+            Value::List(values) => values.iter().collect(),
+            value => vec![*value],
+        })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Group<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.values.as_slice() {
+            [value] => value.deserialize_any(visitor),
+            _ => self.deserialize_seq(visitor),
+        }
+    }
+
+    forward_to_scalar! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_identifier
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    // A single entry keeps its own representation, e.g. a blank separated
+    // string, repeated entries are the elements.
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.values.as_slice() {
+            [value] => value.deserialize_seq(visitor),
+            _ => {
+                let elements: Vec<_> = self.elements().collect();
+                visitor.visit_seq(ListSeq(elements.into_iter()))
+            }
+        }
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_map(visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.single()?.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+// Elements of a `List`.
+struct ListSeq<I>(I);
+
+impl<'de, I> SeqAccess<'de> for ListSeq<I>
+where
+    I: Iterator<Item = &'de Value>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.0.next() {
+            Some(value) => seed.deserialize(value).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+// Elements of an `Array`, each one parsed like a scalar.
+struct ArraySeq<'de>(std::slice::Iter<'de, String>);
+
+impl<'de> SeqAccess<'de> for ArraySeq<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let element = match self.0.next() {
+            Some(element) => element,
+            None => return Ok(None),
+        };
+        let mut de = ValueDeserializer::new(element);
+        let value = seed.deserialize(&mut de)?;
+        de.end()?;
+        Ok(Some(value))
+    }
+}
+
+// Map access grouping repeated keys of a block, in the order of their first
+// appearance.
+struct BlockAccess<'de> {
+    groups: std::vec::IntoIter<(&'de str, Vec<&'de Value>)>,
+    values: Vec<&'de Value>,
+}
+
+impl<'de> BlockAccess<'de> {
+    fn new(entries: &'de [(String, Value)]) -> Self {
+        let mut groups: Vec<(&str, Vec<&Value>)> = vec![];
+        for (key, value) in entries {
+            match groups.iter_mut().find(|(group, _)| group == key) {
+                Some((_, values)) => values.push(value),
+                None => groups.push((key, vec![value])),
+            }
+        }
+        BlockAccess {
+            groups: groups.into_iter(),
+            values: vec![],
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for BlockAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.groups.next() {
+            Some((key, values)) => {
+                self.values = values;
+                seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let values = std::mem::take(&mut self.values);
+        seed.deserialize(Group { values })
+    }
+}
+
+// Map access to the entries of a block in their order, keys might repeat.
+struct EntryAccess<'de> {
+    entries: std::slice::Iter<'de, (String, Value)>,
+    value: Option<&'de Value>,
+}
+
+impl<'de> EntryAccess<'de> {
+    fn new(entries: &'de [(String, Value)]) -> Self {
+        EntryAccess {
+            entries: entries.iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for EntryAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(Error::Eof),
+        }
+    }
+}
+
+// The two fields of a `File`, the header and the block as body.
+struct FileAccess<'de> {
+    header: &'de str,
+    body: &'de Value,
+    field: usize,
+}
+
+impl<'de> MapAccess<'de> for FileAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let key = match self.field {
+            0 => file::HEADER,
+            1 => file::BODY,
+            _ => return Ok(None),
+        };
+        seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.field += 1;
+        match self.field {
+            1 => seed.deserialize(HeaderDeserializer::new(self.header)),
+            _ => seed.deserialize(self.body),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Conversion of the values in the `cs2!` macro. Literals are converted from
+// their source text where it matters, e.g. to keep hexadecimal numbers.
+
+use std::fmt::Display;
+
+use super::Value;
+
+#[doc(hidden)]
+pub trait Literal {
+    fn to_value(&self, text: &str) -> Value;
+}
+
+impl Literal for &str {
+    fn to_value(&self, _text: &str) -> Value {
+        Value::String((*self).to_owned())
+    }
+}
+
+impl Literal for bool {
+    fn to_value(&self, _text: &str) -> Value {
+        Value::String(if *self { "1" } else { "0" }.to_owned())
+    }
+}
+
+impl Literal for char {
+    fn to_value(&self, _text: &str) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl Literal for f32 {
+    fn to_value(&self, _text: &str) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl Literal for f64 {
+    fn to_value(&self, _text: &str) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+// Integers are written like in the source without separators and type
+// suffix, so hexadecimal numbers stay hexadecimal. The value itself might
+// have overflowed, an unsuffixed literal is an `i32` even for an `mfxuid`.
+macro_rules! integer_literal {
+    ($($ty:ty)*) => {
+        $(
+            impl Literal for $ty {
+                fn to_value(&self, text: &str) -> Value {
+                    let (sign, digits) = match text.strip_prefix('-') {
+                        Some(digits) => ("-", digits.trim_start()),
+                        None => ("", text),
+                    };
+                    let (prefix, digits) = match digits.strip_prefix("0x") {
+                        Some(hex) => ("0x", hex),
+                        None if digits.starts_with("0o") || digits.starts_with("0b") => {
+                            return Value::String(self.to_string());
+                        }
+                        None => ("", digits),
+                    };
+                    let hex = !prefix.is_empty();
+                    let digits: String = digits
+                        .chars()
+                        .take_while(|c| *c == '_' || c.is_ascii_digit() || hex && c.is_ascii_hexdigit())
+                        .filter(|c| *c != '_')
+                        .collect();
+                    Value::String(format!("{}{}{}", sign, prefix, digits))
+                }
+            }
+        )*
+    };
+}
+
+integer_literal! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+
+#[doc(hidden)]
+pub fn literal<T: Literal>(text: &str, value: T) -> Value {
+    value.to_value(text)
+}
+
+#[doc(hidden)]
+pub fn expr<T: Display + ?Sized>(value: &T) -> Value {
+    Value::String(value.to_string())
+}
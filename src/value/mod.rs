@@ -0,0 +1,261 @@
+use std::fmt;
+
+use serde::de::{Deserialize, MapAccess, SeqAccess, Visitor};
+use serde::de::DeserializeOwned;
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, SerializeTuple};
+
+use crate::file::Header;
+
+mod de;
+pub(crate) mod literal;
+mod ser;
+
+/// Convert a `T` into a `Value` without writing and parsing the text.
+///
+/// Structs are blocks of their fields, they are not wrapped into a block
+/// named like the struct. Absent options and empty sequences are skipped
+/// like in `to_string`.
+///
+/// ```
+/// use serde_cs2::Value;
+/// use std::collections::BTreeMap;
+///
+/// let map = BTreeMap::from([("name", "Lok"), ("uid", "0x4006")]);
+/// let value = serde_cs2::to_value(&map).unwrap();
+/// assert_eq!(value["uid"], Value::from("0x4006"));
+/// ```
+pub fn to_value<T>(value: &T) -> crate::ser::Result<Value>
+where
+    T: ?Sized + Serialize,
+{
+    Ok(value.serialize(ser::ValueSerializer)?.unwrap_or_default())
+}
+
+/// Convert a `Value` into a `T`. Scalars are parsed like the values of the
+/// text, a block can be deserialized into any struct regardless of its name.
+///
+/// A borrowed `&Value` is a deserializer itself, use `T::deserialize(&value)`
+/// to borrow strings from it.
+pub fn from_value<T>(value: Value) -> crate::Result<T>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(&value)
+}
+
+/// Any cs2 document or part of it without a Rust type describing it.
+///
+/// A document is a `Block` with the entries on the top level. A file header
+/// like `[lokomotive]` is the only entry of the document in that case, with
+/// all entries of the file as its value.
+///
+/// ```
+/// use serde_cs2::Value;
+///
+/// let cs2 = "lokomotive\n .name=Lok\n .funktionen\n ..nr=1\n";
+/// let value: Value = serde_cs2::from_str(cs2).unwrap();
+/// assert_eq!(value["lokomotive"]["name"].as_str(), Some("Lok"));
+/// assert_eq!(serde_cs2::to_string(&value).unwrap(), cs2);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    /// The text behind the '=' of a line.
+    String(String),
+    /// Blank separated values in one line. Parsed documents contain strings
+    /// only, since an array can't be distinguished from a string containing
+    /// blanks.
+    Array(Vec<String>),
+    /// A block with its entries in their original order, keys might repeat.
+    Block(Vec<(String, Value)>),
+    /// Repeated entries with the same key, e.g. all `funktionen` of a
+    /// `lokomotive`.
+    List(Vec<Value>),
+}
+
+impl Default for Value {
+    fn default() -> Self {
+        Value::Block(vec![])
+    }
+}
+
+// A `Value` is the body of any file.
+impl Header for Value {
+    const HEADER: Option<&'static str> = None;
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_block(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Block(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    pub fn as_block_mut(&mut self) -> Option<&mut Vec<(String, Value)>> {
+        match self {
+            Value::Block(entries) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// The value of the first entry with `key` in a block.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_block()?
+            .iter()
+            .find(|(entry, _)| entry == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.as_block_mut()?
+            .iter_mut()
+            .find(|(entry, _)| entry == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The values of all entries with `key` in a block, repeated entries and
+    /// the elements of a `List` in their order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a Value> + 'a {
+        self.as_block()
+            .unwrap_or_default()
+            .iter()
+            .filter(move |(entry, _)| entry == key)
+            .flat_map(|(_, value)| match value {
+                Value::List(values) => values.iter().collect(),
+                value => vec![value],
+            })
+    }
+}
+
+// Indexing a block by key, missing keys return an empty block.
+impl std::ops::Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Value {
+        static EMPTY: Value = Value::Block(Vec::new());
+        self.get(key).unwrap_or(&EMPTY)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Array(values) => {
+                let mut tuple = serializer.serialize_tuple(values.len())?;
+                for value in values {
+                    tuple.serialize_element(value)?;
+                }
+                tuple.end()
+            }
+            Value::Block(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, value)?;
+                }
+                map.end()
+            }
+            Value::List(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any cs2 value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::String(if v { "1" } else { "0" }.to_owned()))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values = vec![];
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::List(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = vec![];
+        while let Some((key, value)) = map.next_entry()? {
+            entries.push((key, value));
+        }
+        Ok(Value::Block(entries))
+    }
+}
@@ -0,0 +1,330 @@
+use serde::ser::{self, Impossible, Serialize};
+
+use super::Value;
+use crate::file;
+use crate::ser::{Error, KeySerializer, Result, UnsupportedType};
+
+// Serializer building a `Value` instead of text. It produces the same tree
+// `from_str` would produce for the text written by `to_string`, except that
+// structs are not named on the top level but represented by their block.
+//
+// `None` represents a value which is not written at all, e.g. an absent
+// optional or an empty sequence, the entry is skipped in that case.
+pub(crate) struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    type SerializeSeq = ListBuilder;
+    type SerializeTuple = ArrayBuilder;
+    type SerializeTupleStruct = ArrayBuilder;
+    type SerializeTupleVariant = Impossible<Option<Value>, Error>;
+    type SerializeMap = BlockBuilder;
+    type SerializeStruct = BlockBuilder;
+    type SerializeStructVariant = Impossible<Option<Value>, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Option<Value>> {
+        Ok(Some(Value::String(if v { "1" } else { "0" }.to_owned())))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Option<Value>> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Option<Value>> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Option<Value>> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Option<Value>> {
+        Ok(Some(Value::String(v.to_string())))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Option<Value>> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Option<Value>> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Option<Value>> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Option<Value>> {
+        Ok(Some(Value::String(v.to_string())))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Option<Value>> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Option<Value>> {
+        Ok(Some(Value::String(v.to_string())))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Option<Value>> {
+        Ok(Some(Value::String(v.to_string())))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Option<Value>> {
+        Ok(Some(Value::String(v.to_owned())))
+    }
+
+    // Bytes are the raw text of a value, see `Serializer::serialize_bytes`
+    fn serialize_bytes(self, v: &[u8]) -> Result<Option<Value>> {
+        match std::str::from_utf8(v) {
+            Ok(s) => self.serialize_str(s),
+            Err(e) => Err(Error::Custom(e.to_string())),
+        }
+    }
+
+    fn serialize_none(self) -> Result<Option<Value>> {
+        Ok(None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Option<Value>>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Option<Value>> {
+        Err(Error::UnsupportedType(UnsupportedType::Unit))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Option<Value>> {
+        Err(Error::UnsupportedType(UnsupportedType::UnitStruct))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Option<Value>> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Option<Value>>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Option<Value>>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::UnsupportedType(UnsupportedType::NewtypeVariant))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(ListBuilder {
+            values: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        Ok(ArrayBuilder {
+            values: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(Error::UnsupportedType(UnsupportedType::TupleVariant))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(BlockBuilder::new(len.unwrap_or_default(), false))
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        Ok(BlockBuilder::new(len, name == file::NAME))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(Error::UnsupportedType(UnsupportedType::StructVariant))
+    }
+}
+
+// Elements of a sequence are repeated entries, an empty sequence is skipped.
+pub(crate) struct ListBuilder {
+    values: Vec<Value>,
+}
+
+impl ser::SerializeSeq for ListBuilder {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        if let Some(value) = value.serialize(ValueSerializer)? {
+            self.values.push(value);
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<Value>> {
+        if self.values.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(Value::List(self.values)))
+        }
+    }
+}
+
+// Tuples are arrays of scalars in a single line.
+pub(crate) struct ArrayBuilder {
+    values: Vec<String>,
+}
+
+impl ser::SerializeTuple for ArrayBuilder {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        match value.serialize(ValueSerializer)? {
+            Some(Value::String(s)) => self.values.push(s),
+            None => self.values.push(String::new()),
+            Some(_) => return Err(Error::Custom("arrays cannot be nested".to_owned())),
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<Value>> {
+        Ok(Some(Value::Array(self.values)))
+    }
+}
+
+impl ser::SerializeTupleStruct for ArrayBuilder {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeTuple::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Option<Value>> {
+        ser::SerializeTuple::end(self)
+    }
+}
+
+// Structs and maps are blocks. A `File` becomes a block with the header in
+// brackets as only key.
+pub(crate) struct BlockBuilder {
+    entries: Vec<(String, Value)>,
+    key: Option<String>,
+    file: bool,
+}
+
+impl BlockBuilder {
+    fn new(len: usize, file: bool) -> Self {
+        BlockBuilder {
+            entries: Vec::with_capacity(len),
+            key: None,
+            file,
+        }
+    }
+
+    fn push(&mut self, key: String, value: Option<Value>) {
+        if let Some(value) = value {
+            self.entries.push((key, value));
+        }
+    }
+}
+
+impl ser::SerializeMap for BlockBuilder {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self.key.take().unwrap_or_default();
+        let value = value.serialize(ValueSerializer)?;
+        self.push(key, value);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<Value>> {
+        Ok(Some(Value::Block(self.entries)))
+    }
+}
+
+impl ser::SerializeStruct for BlockBuilder {
+    type Ok = Option<Value>;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(ValueSerializer)?;
+        if !self.file {
+            self.push(key.to_owned(), value);
+        } else if key == file::HEADER {
+            if let Some(Value::String(header)) = value {
+                self.key = Some(format!("[{}]", header));
+            }
+        } else {
+            let header = self.key.take().unwrap_or_default();
+            self.push(header, Some(value.unwrap_or_default()));
+        }
+        Ok(())
+    }
+
+    fn end(self) -> Result<Option<Value>> {
+        Ok(Some(Value::Block(self.entries)))
+    }
+}
@@ -0,0 +1,64 @@
+//! Conversion between cs2 and YAML, enabled by the `yaml` feature.
+//!
+//! The mapping is the same as for JSON, see the `json` module.
+//!
+//! ```
+//! let cs2 = "lokomotive\n .name=Lok\n .uid=0x4006\n";
+//! let yaml = serde_cs2::yaml::to_string(cs2).unwrap();
+//! assert_eq!(yaml, "lokomotive:\n  name: Lok\n  uid: '0x4006'\n");
+//! assert_eq!(serde_cs2::yaml::from_str(&yaml).unwrap(), cs2);
+//! ```
+
+use std::io;
+
+use crate::bridge;
+pub use crate::bridge::Mapping;
+use crate::error::{Error, Result};
+
+/// Convert a cs2 document into YAML text.
+pub fn to_string(cs2: &str) -> Result<String> {
+    to_string_with(cs2, &Mapping::default())
+}
+
+pub fn to_string_with(cs2: &str, mapping: &Mapping) -> Result<String> {
+    let mut yaml = Vec::new();
+    to_writer_with(&mut yaml, cs2, mapping)?;
+    String::from_utf8(yaml).map_err(|e| Error::Message(e.to_string()))
+}
+
+/// Write a cs2 document as YAML into `writer`.
+pub fn to_writer_with<W>(writer: W, cs2: &str, mapping: &Mapping) -> Result<()>
+where
+    W: io::Write,
+{
+    let mut serializer = serde_norway::Serializer::new(writer);
+    bridge::serialize(cs2, mapping, &mut serializer)
+}
+
+/// Convert a cs2 document into a `serde_norway::Value`.
+pub fn to_value(cs2: &str) -> Result<serde_norway::Value> {
+    to_value_with(cs2, &Mapping::default())
+}
+
+pub fn to_value_with(cs2: &str, mapping: &Mapping) -> Result<serde_norway::Value> {
+    bridge::serialize(cs2, mapping, serde_norway::value::Serializer)
+}
+
+/// Convert YAML text into a cs2 document. The top level has to be a
+/// mapping, `null` is not supported.
+pub fn from_str(yaml: &str) -> Result<String> {
+    from_str_with(yaml, &Mapping::default())
+}
+
+pub fn from_str_with(yaml: &str, mapping: &Mapping) -> Result<String> {
+    bridge::transcode(serde_norway::Deserializer::from_str(yaml), mapping)
+}
+
+/// Convert a `serde_norway::Value` into a cs2 document.
+pub fn from_value(yaml: &serde_norway::Value) -> Result<String> {
+    from_value_with(yaml, &Mapping::default())
+}
+
+pub fn from_value_with(yaml: &serde_norway::Value, mapping: &Mapping) -> Result<String> {
+    bridge::to_string(yaml, mapping)
+}
@@ -0,0 +1,117 @@
+use serde_cs2::{ChangeKind, Identity, Value};
+
+const OLD: &str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .adresse=0x6
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=3
+ ..typ=131
+lokomotive
+ .name=BR 103
+ .uid=0x4007
+lokomotive
+ .name=V 60
+ .uid=0x4008
+traktion
+ .lok=BR 218
+ .lok=BR 103
+"#;
+
+const NEW: &str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 120
+ .uid=0x4009
+lokomotive
+ .name=BR 103
+ .uid=0x4007
+lokomotive
+ .name=BR 218 TEE
+ .uid=0x4006
+ .adresse=0x7
+ .funktionen
+ ..nr=3
+ ..typ=5
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .vmax=160
+traktion
+ .lok=BR 218
+"#;
+
+fn parse(cs2: &str) -> Value {
+    serde_cs2::from_str(cs2).unwrap()
+}
+
+#[test]
+fn diff_by_identity() {
+    let diff = serde_cs2::diff(&parse(OLD), &parse(NEW));
+    assert_eq!(
+        diff.to_string(),
+        r#"~ lokomotive[uid=0x4006].name: BR 218 -> BR 218 TEE
+~ lokomotive[uid=0x4006].adresse: 0x6 -> 0x7
+~ lokomotive[uid=0x4006].funktionen[nr=3].typ: 131 -> 5
++ lokomotive[uid=0x4006].vmax=160
+- lokomotive[uid=0x4008]
++ lokomotive[uid=0x4009]
+~ traktion.lok: [BR 218, BR 103] -> [BR 218]
+"#
+    );
+
+    let removed = &diff.changes()[4];
+    assert_eq!(removed.kind, ChangeKind::Removed);
+    assert_eq!(removed.old.as_ref().unwrap()["name"].as_str(), Some("V 60"));
+    assert_eq!(removed.new, None);
+
+    // the paths select the changed entries
+    let typ = serde_cs2::select(NEW, &diff.changes()[2].path).unwrap();
+    assert_eq!(typ[0].value(), Some("5"));
+
+    assert!(serde_cs2::diff(&parse(OLD), &parse(OLD)).is_empty());
+}
+
+#[test]
+fn diff_identity_keys() {
+    let old = parse("lokomotive\n .name=Lok\n .uid=0x1\nlokomotive\n .name=Lok 2\n .uid=0x2\n");
+    let new = parse("lokomotive\n .name=Lok\n .uid=0x3\nlokomotive\n .name=Lok 2\n .uid=0x2\n");
+
+    let by_uid = serde_cs2::diff(&old, &new);
+    assert_eq!(by_uid.to_string(), "- lokomotive[uid=0x1]\n+ lokomotive[uid=0x3]\n");
+
+    let by_name = serde_cs2::diff_with(&old, &new, &Identity::new(["name"]));
+    assert_eq!(by_name.to_string(), "~ lokomotive[name=Lok].uid: 0x1 -> 0x3\n");
+
+    let by_position = serde_cs2::diff_with(&old, &new, &Identity::new(Vec::<String>::new()));
+    assert_eq!(by_position.to_string(), "~ lokomotive[0].uid: 0x1 -> 0x3\n");
+}
+
+#[test]
+fn diff_serialize() {
+    let old = parse("lokomotive\n .name=Lok\n .adresse=0x6\n");
+    let new = parse("lokomotive\n .name=Lok\n .adresse=0x7\n .funktionen\n ..nr=0\n");
+    let diff = serde_cs2::diff(&old, &new);
+
+    assert_eq!(
+        serde_cs2::to_string(&diff).unwrap(),
+        r#"change
+ .path=lokomotive[name=Lok].adresse
+ .kind=changed
+ .old=0x6
+ .new=0x7
+change
+ .path=lokomotive[name=Lok].funktionen[nr=0]
+ .kind=added
+ .new
+ ..nr=0
+"#
+    );
+}
@@ -0,0 +1,123 @@
+use serde_cs2::document::Entry;
+use serde_cs2::{Document, Error};
+
+const LOKOMOTIVE_CS2: &str = "[lokomotive]\r
+version\r
+ .minor=3\r
+\r
+lokomotive\r
+ .name=BR 218\r
+ .uid=0x4006\r
+   .adresse=0x6\r
+ .unbekannt= trailing  \r
+ .funktionen\r
+ ..nr=0\r
+ ..typ=1\r
+  \r
+lokomotive  \r
+ .name=BR 103\r
+ .uid=0x4007\r
+\r
+";
+
+#[test]
+fn document_round_trip() {
+    let doc: Document = LOKOMOTIVE_CS2.parse().unwrap();
+    assert_eq!(doc.to_string(), LOKOMOTIVE_CS2);
+    assert_eq!(doc.header(), Some("lokomotive"));
+    assert_eq!(doc.get_all("lokomotive").count(), 2);
+
+    let lok = doc.get("lokomotive").unwrap().block().unwrap();
+    assert_eq!(lok.value("adresse"), Some("0x6"));
+    assert_eq!(lok.value("unbekannt"), Some(" trailing  "));
+    assert_eq!(lok.get("funktionen").unwrap().block().unwrap().len(), 2);
+
+    for input in ["", "\n\n", "lokomotive\n .name=Lok", "\n[gleisbild]\nversion\n .major=1"] {
+        let doc: Document = input.parse().unwrap();
+        assert_eq!(doc.to_string(), input);
+    }
+}
+
+#[test]
+fn document_edit_in_place() {
+    let mut doc: Document = LOKOMOTIVE_CS2.parse().unwrap();
+    let lok = doc
+        .get_all_mut("lokomotive")
+        .find(|lok| lok.block().unwrap().value("name") == Some("BR 103"))
+        .unwrap()
+        .block_mut()
+        .unwrap();
+    lok.set("name", "BR 103 TEE");
+    lok.set("adresse", "0x7");
+    lok.push(Entry::with_block("funktionen"))
+        .block_mut()
+        .unwrap()
+        .set("nr", "0");
+
+    let lok = doc.get_mut("lokomotive").unwrap().block_mut().unwrap();
+    lok.set("adresse", "0x8");
+    lok.remove("unbekannt").unwrap();
+
+    assert_eq!(
+        doc.to_string(),
+        "[lokomotive]\r
+version\r
+ .minor=3\r
+\r
+lokomotive\r
+ .name=BR 218\r
+ .uid=0x4006\r
+   .adresse=0x8\r
+ .funktionen\r
+ ..nr=0\r
+ ..typ=1\r
+  \r
+lokomotive  \r
+ .name=BR 103 TEE\r
+ .uid=0x4007\r
+ .adresse=0x7\r
+ .funktionen\r
+ ..nr=0\r
+\r
+"
+    );
+}
+
+#[test]
+fn document_insert_and_remove_blocks() {
+    let mut doc: Document = "version\n .minor=3\nlokomotive\n .name=Lok".parse().unwrap();
+    let index = doc.position("lokomotive").unwrap();
+    doc.insert(index, Entry::with_block("session"))
+        .block_mut()
+        .unwrap()
+        .set("id", "1");
+    assert_eq!(
+        doc.to_string(),
+        "version\n .minor=3\nsession\n .id=1\nlokomotive\n .name=Lok"
+    );
+
+    doc.get_mut("lokomotive").unwrap().block_mut().unwrap().set("vmax", "120");
+    assert_eq!(
+        doc.to_string(),
+        "version\n .minor=3\nsession\n .id=1\nlokomotive\n .name=Lok\n .vmax=120\n"
+    );
+
+    doc.remove("version").unwrap();
+    doc.get_mut("session").unwrap().set_value("2");
+    doc.get_mut("lokomotive").unwrap().set_key("lok");
+    assert_eq!(doc.to_string(), "session=2\nlok\n .name=Lok\n .vmax=120\n");
+
+    let mut doc = Document::new(Some("fahrstrassen"));
+    doc.push(Entry::with_value("version", "1"));
+    assert_eq!(doc.to_string(), "[fahrstrassen]\nversion=1\n");
+}
+
+#[test]
+fn document_errors() {
+    assert_eq!(" .name=Lok\n".parse::<Document>(), Err(Error::WrongLevel));
+    assert_eq!("lok\n...name=Lok\n".parse::<Document>(), Err(Error::WrongLevel));
+    assert_eq!(
+        "lok\n .name=Lok\n[lokomotive]\n".parse::<Document>(),
+        Err(Error::UnexpectedHeader)
+    );
+}
@@ -0,0 +1,76 @@
+use serde_cs2::models::{FahrstrassenFile, MagnetartikelFile, Stellung};
+use serde_cs2::File;
+
+const FAHRSTRASSEN_CS2: &str = r#"[fahrstrassen]
+version
+ .minor=4
+fahrstrasse
+ .id=1
+ .name=Einfahrt Gleis 2
+ .s88=12
+ .s88Ein=1
+ .item
+ ..magnetartikel=5
+ ..stellung=0
+ ..sekunde=0.5
+ .item
+ ..magnetartikel=7
+ ..stellung=1
+ .item
+ ..magnetartikel=99
+ ..stellung=1
+fahrstrasse
+ .id=2
+ .name=Ausfahrt
+ .item
+ ..magnetartikel=17
+ ..stellung=1
+ ..typ=mag
+"#;
+
+const MAGNETARTIKEL_CS2: &str = r#"[magnetartikel]
+artikel
+ .id=5
+ .name=W5
+ .typ=linksweiche
+artikel
+ .id=7
+ .name=W7
+ .typ=rechtsweiche
+"#;
+
+#[test]
+fn fahrstrassen_round_trip() {
+    let file: File<FahrstrassenFile> = serde_cs2::from_str(FAHRSTRASSEN_CS2).unwrap();
+    let fahrstrasse = &file.body().fahrstrasse;
+    assert_eq!(fahrstrasse.len(), 2);
+    assert_eq!(fahrstrasse[0].s88, Some(12));
+    assert_eq!(fahrstrasse[0].s88_ein, Some(1));
+    assert_eq!(fahrstrasse[0].item.len(), 3);
+    assert_eq!(fahrstrasse[0].item[0].stellung, Some(Stellung::Rot));
+    assert_eq!(fahrstrasse[0].item[0].sekunde, Some(0.5));
+    assert_eq!(fahrstrasse[1].s88, None);
+    assert_eq!(fahrstrasse[1].item.len(), 1);
+    assert!(fahrstrasse[1].item[0].unknown.contains_key("typ"));
+
+    assert_eq!(FAHRSTRASSEN_CS2, serde_cs2::to_string(&file).unwrap());
+}
+
+#[test]
+fn fahrstrassen_resolve() {
+    let fahrstrassen: File<FahrstrassenFile> = serde_cs2::from_str(FAHRSTRASSEN_CS2).unwrap();
+    let magnetartikel: File<MagnetartikelFile> = serde_cs2::from_str(MAGNETARTIKEL_CS2).unwrap();
+
+    let names: Vec<Option<&str>> = fahrstrassen.body().fahrstrasse[0]
+        .resolve(magnetartikel.body())
+        .map(|(_, artikel)| artikel.and_then(|artikel| artikel.name.as_deref()))
+        .collect();
+    assert_eq!(names, [Some("W5"), Some("W7"), None]);
+}
+
+#[test]
+fn fahrstrassen_invalid_stellung() {
+    let cs2 = "[fahrstrassen]\nfahrstrasse\n .id=1\n .item\n ..magnetartikel=5\n ..stellung=7\n";
+    let error = serde_cs2::from_str::<File<FahrstrassenFile>>(cs2).unwrap_err();
+    assert_eq!(error.to_string(), "invalid stellung 7");
+}
@@ -0,0 +1,81 @@
+use serde_cs2::models::{Element, GleisbildFile, GleisbildseiteFile, Position};
+use serde_cs2::File;
+
+#[test]
+fn gleisbild_deserialize() {
+    let cs2 = r#"[gleisbild]
+version
+ .major=1
+seite
+ .name=Bahnhof
+seite
+ .id=1
+ .name=Strecke
+zuletztBenutzt
+ .name=Bahnhof
+"#;
+    let file: File<GleisbildFile> = serde_cs2::from_str(cs2).unwrap();
+    let seite = &file.body().seite;
+    assert_eq!(seite.len(), 2);
+    assert_eq!((seite[0].id, seite[0].name.as_str()), (None, "Bahnhof"));
+    assert_eq!((seite[1].id, seite[1].name.as_str()), (Some(1), "Strecke"));
+    assert!(file.body().unknown.contains_key("zuletztBenutzt"));
+
+    assert_eq!(cs2, serde_cs2::to_string(&file).unwrap());
+}
+
+#[test]
+fn gleisbildseite_deserialize() {
+    let cs2 = r#"[gleisbildseite]
+version
+ .major=1
+element
+ .typ=gerade
+element
+ .id=0x10203
+ .typ=linksweiche
+ .drehung=2
+ .artikel=5
+element
+ .id=0x1020a
+ .typ=text
+ .text=Gleis 1
+"#;
+    let file: File<GleisbildseiteFile> = serde_cs2::from_str(cs2).unwrap();
+    let element = &file.body().element;
+    assert_eq!(element[0].position(), Position { seite: 0, x: 0, y: 0 });
+    assert_eq!(element[1].position(), Position { seite: 1, x: 3, y: 2 });
+    assert_eq!(element[1].drehung, Some(2));
+    assert_eq!(element[1].artikel, Some(5));
+    assert_eq!(element[2].text.as_deref(), Some("Gleis 1"));
+
+    assert_eq!(cs2, serde_cs2::to_string(&file).unwrap());
+}
+
+#[test]
+fn gleisbildseite_serialize() {
+    let mut signal = Element {
+        typ: "signal".to_owned(),
+        artikel: Some(-1),
+        ..Default::default()
+    };
+    signal.set_position(Position { seite: 0, x: 12, y: 4 });
+    let mut gerade = Element {
+        typ: "gerade".to_owned(),
+        id: Some(5),
+        ..Default::default()
+    };
+    gerade.set_position(Position::default());
+
+    let file = File::new(
+        "gleisbildseite",
+        GleisbildseiteFile {
+            element: vec![gerade, signal],
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        serde_cs2::to_string(&file).unwrap(),
+        "[gleisbildseite]\nelement\n .typ=gerade\nelement\n .id=0x40c\n .typ=signal\n .artikel=-1\n"
+    );
+}
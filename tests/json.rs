@@ -0,0 +1,104 @@
+use serde_cs2::json::{self, Mapping};
+use serde_json::json;
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .adresse=0x6
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=1
+ ..typ=131
+ .blocks=0 0 0 0
+traktion
+ .lok=BR 218
+ .lok=BR 103
+"#;
+
+#[test]
+fn json_round_trip() {
+    let value = json::to_value(LOKOMOTIVE_CS2).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "[lokomotive]": {
+                "version": { "minor": "3" },
+                "lokomotive": {
+                    "name": "BR 218",
+                    "uid": "0x4006",
+                    "adresse": "0x6",
+                    "funktionen": [
+                        { "nr": "0", "typ": "1" },
+                        { "nr": "1", "typ": "131" }
+                    ],
+                    "blocks": "0 0 0 0"
+                },
+                "traktion": { "lok": ["BR 218", "BR 103"] }
+            }
+        })
+    );
+
+    let text = json::to_string(LOKOMOTIVE_CS2).unwrap();
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&text).unwrap(), value);
+    assert_eq!(json::from_str(&text).unwrap(), LOKOMOTIVE_CS2);
+    // A `serde_json::Value` keeps the order of the keys with the
+    // `preserve_order` feature only.
+    #[cfg(feature = "preserve_order")]
+    assert_eq!(json::from_value(&value).unwrap(), LOKOMOTIVE_CS2);
+}
+
+#[test]
+fn json_hex_keys() {
+    let mapping = Mapping::new().hex_keys(["uid", "adresse"]);
+    let value = json::to_value_with(LOKOMOTIVE_CS2, &mapping).unwrap();
+    let lok = &value["[lokomotive]"]["lokomotive"];
+    assert_eq!(lok["uid"], json!(16390));
+    assert_eq!(lok["adresse"], json!(6));
+    assert_eq!(lok["funktionen"][1]["typ"], json!("131"));
+
+    let cs2 = json::from_value(&value).unwrap();
+    assert!(cs2.contains(" .uid=16390\n"));
+
+    #[cfg(feature = "preserve_order")]
+    assert_eq!(json::from_value_with(&value, &mapping).unwrap(), LOKOMOTIVE_CS2);
+    let text = json::to_string_with(LOKOMOTIVE_CS2, &mapping).unwrap();
+    assert_eq!(json::from_str_with(&text, &mapping).unwrap(), LOKOMOTIVE_CS2);
+
+    // Leading zeros would get lost, such values stay strings.
+    let value = json::to_value_with("lok\n .uid=0x0006\n", &mapping).unwrap();
+    assert_eq!(value["lok"]["uid"], json!("0x0006"));
+    assert_eq!(json::from_value_with(&value, &mapping).unwrap(), "lok\n .uid=0x0006\n");
+}
+
+#[test]
+fn json_hex_keys_keep_decimal() {
+    let mapping = Mapping::new().hex_keys(["uid"]);
+    let cs2 = "lokomotive\n .uid=0x4006\n .vmax=60\n";
+    let text = json::to_string_with(cs2, &mapping).unwrap();
+    assert_eq!(text, r#"{"lokomotive":{"uid":16390,"vmax":"60"}}"#);
+    assert_eq!(json::from_str_with(&text, &mapping).unwrap(), cs2);
+
+    // Numbers of other keys are written in decimal, even if they were
+    // edited into numbers on the JSON side.
+    let value = json!({ "lokomotive": { "uid": 16390, "vmax": 60 } });
+    assert_eq!(json::from_value_with(&value, &mapping).unwrap(), cs2);
+}
+
+#[test]
+fn json_into_cs2() {
+    let cs2 = json::from_str(
+        r#"{"lokomotive": [{"name": "Lok", "vmax": 120, "rückwärts": true}, {"name": "Lok 2"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        cs2,
+        "lokomotive\n .name=Lok\n .vmax=120\n .rückwärts=1\nlokomotive\n .name=Lok 2\n"
+    );
+    assert!(json::from_str(r#"{"lokomotive": {"name": null}}"#).is_err());
+    assert!(json::from_str(r#"{"lokomotive": {}} trailing"#).is_err());
+}
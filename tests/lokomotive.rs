@@ -1,10 +1,9 @@
-use serde_cs2;
+use serde_cs2::{File, Header};
 use serde_derive::{Deserialize, Serialize};
 use serde_hex::{SerHex, SerHexOpt, StrictPfx, CompactPfx};
 
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-#[serde(rename = "funktionen_2")]
 pub struct Funktionen2 {
     pub nr: u8,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -14,7 +13,6 @@ pub struct Funktionen2 {
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
-#[serde(rename = "funktionen")]
 pub struct Funktionen {
     pub nr: u8,
     pub typ: u16,
@@ -241,3 +239,285 @@ fn lokomotive_deserialize() {
 
     assert_eq!(expected, serde_cs2::from_str(&serde_cs2::to_string(&expected).unwrap()).unwrap());
 }
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename = "traktion")]
+struct Traktion {
+    name: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct LokomotiveFile {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    lokomotive: Vec<Lokomotive>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    traktion: Vec<Traktion>,
+}
+
+impl Header for LokomotiveFile {
+    const HEADER: Option<&'static str> = Some("lokomotive");
+}
+
+#[test]
+fn lokomotive_deserialize_interleaved() {
+    let cs2 = r#"[lokomotive]
+lokomotive
+ .name=Lok 1
+ .uid=0x4001
+ .adresse=0x1
+ .funktionen
+ ..nr=1
+ ..typ=1
+ ..dauer=0
+ ..wert=0
+ .funktionen_2
+ ..nr=16
+ ..dauer=0
+ ..wert=0
+ .funktionen
+ ..nr=2
+ ..typ=2
+ ..dauer=0
+ ..wert=0
+traktion
+ .name=Doppeltraktion
+lokomotive
+ .name=Lok 2
+ .uid=0x4002
+ .adresse=0x2
+traktion
+ .name=Schiebelok
+"#;
+
+    let file: File<LokomotiveFile> = serde_cs2::from_str(cs2).unwrap();
+    let file = file.into_body();
+    assert_eq!(file.lokomotive.len(), 2);
+    assert_eq!(file.lokomotive[0].name, "Lok 1");
+    assert_eq!(file.lokomotive[1].name, "Lok 2");
+    assert_eq!(
+        file.lokomotive[0].funktionen.iter().map(|f| f.nr).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    assert_eq!(file.lokomotive[0].funktionen_2.len(), 1);
+    assert_eq!(
+        file.traktion,
+        vec![
+            Traktion { name: "Doppeltraktion".to_owned() },
+            Traktion { name: "Schiebelok".to_owned() },
+        ]
+    );
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename = "lokomotive")]
+struct LokomotiveShared {
+    name: String,
+    funktionen: Vec<Funktionen>,
+    funktionen_2: Vec<Funktionen>,
+}
+
+#[test]
+fn lokomotive_nested_named_by_field() {
+    let lokomotive = LokomotiveShared {
+        name: "Lok".to_owned(),
+        funktionen: vec![Funktionen { nr: 1, typ: 1, dauer: 0, wert: 0 }],
+        funktionen_2: vec![
+            Funktionen { nr: 16, typ: 16, dauer: 0, wert: 0 },
+            Funktionen { nr: 17, typ: 17, dauer: 0, wert: 1 },
+        ],
+    };
+
+    let cs2 = r#"lokomotive
+ .name=Lok
+ .funktionen
+ ..nr=1
+ ..typ=1
+ ..dauer=0
+ ..wert=0
+ .funktionen_2
+ ..nr=16
+ ..typ=16
+ ..dauer=0
+ ..wert=0
+ .funktionen_2
+ ..nr=17
+ ..typ=17
+ ..dauer=0
+ ..wert=1
+"#;
+
+    assert_eq!(cs2, serde_cs2::to_string(&lokomotive).unwrap());
+    assert_eq!(lokomotive, serde_cs2::from_str(cs2).unwrap());
+}
+
+#[test]
+fn lokomotive_file_header() {
+    let cs2 = r#"[lokomotive]
+traktion
+ .name=Doppeltraktion
+"#;
+
+    let file = File::new(
+        "lokomotive",
+        LokomotiveFile {
+            lokomotive: vec![],
+            traktion: vec![Traktion { name: "Doppeltraktion".to_owned() }],
+        },
+    );
+    assert_eq!(cs2, serde_cs2::to_string(&file).unwrap());
+
+    let parsed: File<LokomotiveFile> = serde_cs2::from_str(cs2).unwrap();
+    assert_eq!(file, parsed);
+
+    assert_eq!(
+        Err(serde_cs2::Error::WrongHeader {
+            expected: "fahrstrassen".to_owned(),
+            found: "lokomotive".to_owned(),
+        }),
+        parsed.expect_header("fahrstrassen")
+    );
+
+    let result: serde_cs2::Result<File<LokomotiveFile>> =
+        serde_cs2::from_str("[fahrstrassen]\ntraktion\n .name=Doppeltraktion\n");
+    assert_eq!(
+        Err(serde_cs2::Error::WrongHeader {
+            expected: "lokomotive".to_owned(),
+            found: "fahrstrassen".to_owned(),
+        }),
+        result
+    );
+
+    let result: serde_cs2::Result<File<LokomotiveFile>> =
+        serde_cs2::from_str("traktion\n .name=Doppeltraktion\n");
+    assert_eq!(Err(serde_cs2::Error::ExpectedHeader), result);
+
+    let result: serde_cs2::Result<Traktion> = serde_cs2::from_str(cs2);
+    assert_eq!(Err(serde_cs2::Error::UnexpectedHeader), result);
+}
+
+#[test]
+fn lokomotive_append() {
+    let traktion = Traktion { name: "Schiebelok".to_owned() };
+
+    let mut cs2 = String::from("[lokomotive]\r\nversion\r\n .minor=3\r\ntraktion\r\n .name=Doppeltraktion\r\n .unbekannt=1\r\nlokomotive\r\n .name=Lok\r\n");
+    serde_cs2::insert_into_string(&mut cs2, "traktion", &traktion).unwrap();
+    assert_eq!(
+        "[lokomotive]\r\nversion\r\n .minor=3\r\ntraktion\r\n .name=Doppeltraktion\r\n .unbekannt=1\r\ntraktion\r\n .name=Schiebelok\r\nlokomotive\r\n .name=Lok\r\n",
+        cs2
+    );
+
+    let mut cs2 = String::from("[lokomotive]\nversion\n .minor=3");
+    serde_cs2::insert_into_string(&mut cs2, "traktion", &traktion).unwrap();
+    assert_eq!("[lokomotive]\nversion\n .minor=3\ntraktion\n .name=Schiebelok\n", cs2);
+
+    let mut output = b"[lokomotive]\n".to_vec();
+    serde_cs2::append_to_writer(&mut output, "traktion", &traktion).unwrap();
+    assert_eq!(b"[lokomotive]\ntraktion\n .name=Schiebelok\n".to_vec(), output);
+
+    assert!(serde_cs2::append_to_writer(&mut output, "traktion", &5u8).is_err());
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename = "lokomotive")]
+struct LokomotiveOptional {
+    name: String,
+    mfxuid: Option<u32>,
+    funktionen: Vec<Funktionen>,
+    blocks: Option<[u8; 4]>,
+}
+
+#[test]
+fn lokomotive_serialize_skips_empty() {
+    let lokomotive = LokomotiveOptional {
+        name: "Lok".to_owned(),
+        mfxuid: None,
+        funktionen: vec![],
+        blocks: Some([1, 2, 3, 4]),
+    };
+
+    assert_eq!(
+        "lokomotive\n .name=Lok\n .blocks=1 2 3 4\n",
+        serde_cs2::to_string(&lokomotive).unwrap()
+    );
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+enum Richtung {
+    #[serde(rename = "vor")]
+    Vor,
+    #[serde(rename = "rueck")]
+    Rueck,
+    Faktor(u8),
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Zugnummer(String);
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename = "fahrt")]
+struct Fahrt {
+    zug: Zugnummer,
+    richtung: Richtung,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gleise: Option<(String, String)>,
+}
+
+#[test]
+fn lokomotive_enum_round_trip() {
+    let cs2 = "fahrt\n .zug=RE 1\n .richtung=rueck\n .gleise=3 5a\n";
+    let fahrt: Fahrt = serde_cs2::from_str(cs2).unwrap();
+    assert_eq!(
+        fahrt,
+        Fahrt {
+            zug: Zugnummer("RE 1".to_owned()),
+            richtung: Richtung::Rueck,
+            gleise: Some(("3".to_owned(), "5a".to_owned())),
+        }
+    );
+    assert_eq!(serde_cs2::to_string(&fahrt).unwrap(), cs2);
+
+    assert!(serde_cs2::from_str::<Fahrt>("fahrt\n .zug=RE 1\n .richtung=seitwaerts\n").is_err());
+    assert!(serde_cs2::from_str::<Fahrt>("fahrt\n .zug=RE 1\n .richtung=Faktor\n").is_err());
+    assert!(serde_cs2::from_str::<Fahrt>("fahrt\n .zug=RE 1\n .richtung\n ..vor=1\n").is_err());
+    assert!(serde_cs2::from_str::<Fahrt>("fahrt\n .zug=RE 1\n .richtung=vor\n .richtung=rueck\n").is_err());
+    assert_eq!(serde_cs2::from_str::<Richtung>("vor").unwrap(), Richtung::Vor);
+
+    let fahrt = Fahrt {
+        zug: Zugnummer("RE 2".to_owned()),
+        richtung: Richtung::Faktor(2),
+        gleise: None,
+    };
+    assert!(serde_cs2::to_string(&fahrt).is_err());
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Pair(u8, u8);
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename = "lok")]
+struct LokPair {
+    p: Pair,
+}
+
+#[derive(Debug, Serialize)]
+enum Variante {
+    Tupel(u8, u8),
+    Block { a: u8 },
+}
+
+#[test]
+fn lokomotive_tuple_struct_round_trip() {
+    let cs2 = "lok\n .p=1 2\n";
+    let lok: LokPair = serde_cs2::from_str(cs2).unwrap();
+    assert_eq!(lok, LokPair { p: Pair(1, 2) });
+    assert_eq!(serde_cs2::to_string(&lok).unwrap(), cs2);
+
+    assert_eq!(
+        serde_cs2::to_string(&Variante::Tupel(1, 2)).unwrap_err().to_string(),
+        "TupleVariant cannot be serialized into cs2"
+    );
+    assert_eq!(
+        serde_cs2::to_string(&Variante::Block { a: 1 }).unwrap_err().to_string(),
+        "StructVariant cannot be serialized into cs2"
+    );
+}
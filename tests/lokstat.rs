@@ -1,4 +1,4 @@
-use serde_cs2;
+use serde_cs2::{File, Header};
 use serde_derive::{Deserialize, Serialize};
 
 
@@ -17,7 +17,6 @@ pub struct TrainDecoder {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-#[serde(rename = "version")]
 pub struct Version {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     major: Option<u8>,
@@ -35,12 +34,15 @@ impl Version {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-#[serde(rename = "[lokomotive]")]
 pub struct LokstatFile {
     version: Version,
     lokomotive: Vec<Lokstat>,
 }
 
+impl Header for LokstatFile {
+    const HEADER: Option<&'static str> = Some("lokomotive");
+}
+
 impl Default for LokstatFile {
     fn default() -> Self {
         Self {
@@ -58,7 +60,6 @@ impl LokstatFile {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-#[serde(rename = "funktionen")]
 pub struct FunktionStatus {
     nr: u8,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -243,7 +244,7 @@ fn lokstat_file_simple() {
 
     let mut lokstat = LokstatFile::default();
     lokstat.add_train(&train);
-    let serialized = serde_cs2::to_string(&lokstat).unwrap();
+    let serialized = serde_cs2::to_string(&File::new("lokomotive", lokstat)).unwrap();
 
     let cs2_str = r#"[lokomotive]
 version
@@ -321,7 +322,9 @@ lokomotive
 
     assert_eq!(serialized, cs2_str);
 
-    let lokstat_file: LokstatFile = serde_cs2::from_str(serialized.as_str()).unwrap();
+    let lokstat_file: File<LokstatFile> = serde_cs2::from_str(serialized.as_str()).unwrap();
+    assert_eq!(lokstat_file.header(), "lokomotive");
+    let lokstat_file = lokstat_file.into_body();
     assert_eq!(lokstat_file.lokomotive.len(), 1);
 
     let lokstat = lokstat_file.lokomotive.first().unwrap();
@@ -351,7 +354,7 @@ fn lokstat_file_full() {
     train.functions.push(TrainFunction{ id: 0, duration: 1, typ: 1, value: 1 });
     lokstat.add_train(&train);
 
-    let serialized = serde_cs2::to_string(&lokstat).unwrap();
+    let serialized = serde_cs2::to_string(&File::new("lokomotive", lokstat)).unwrap();
 
     let cs2_str = r#"[lokomotive]
 version
@@ -498,7 +501,9 @@ lokomotive
 
     assert_eq!(serialized, cs2_str);
 
-    let lokstat_file: LokstatFile = serde_cs2::from_str(serialized.as_str()).unwrap();
+    let lokstat_file: File<LokstatFile> = serde_cs2::from_str(serialized.as_str()).unwrap();
+    assert_eq!(lokstat_file.header(), "lokomotive");
+    let lokstat_file = lokstat_file.into_body();
     assert_eq!(lokstat_file.lokomotive.len(), 2);
 
     let lokstat = lokstat_file.lokomotive.first().unwrap();
@@ -0,0 +1,96 @@
+use serde_cs2::{cs2, File, Header, Value};
+use serde_derive::Deserialize;
+use serde_hex::{SerHex, SerHexOpt, StrictPfx};
+
+#[test]
+fn macro_matches_parsed_document() {
+    let parsed: Value = serde_cs2::from_str(
+        r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .mfxuid=0xffff8d2a
+ .adresse=6
+ .vmax=1000
+ .blocks=0 0 0 0
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=1
+ ..dauer=-1
+ .fahrtrichtung=1
+ .symbol=R
+"#,
+    )
+    .unwrap();
+
+    let built = cs2! {
+        [lokomotive]
+        version { minor: 3 }
+        lokomotive {
+            name: "BR 218",
+            uid: 0x4006,
+            mfxuid: 0xffff_8d2a,
+            adresse: 6u16,
+            vmax: 1_000,
+            blocks: "0 0 0 0",
+            funktionen [
+                { nr: 0, typ: 1 },
+                { nr: 1, dauer: -1 },
+            ],
+            fahrtrichtung: true,
+            symbol: 'R',
+        }
+    };
+    assert_eq!(built, parsed);
+}
+
+#[derive(Debug, Deserialize)]
+struct Lokomotive {
+    name: String,
+    #[serde(with = "SerHex::<StrictPfx>")]
+    uid: u16,
+    #[serde(default, with = "SerHexOpt::<StrictPfx>")]
+    mfxuid: Option<u32>,
+    #[serde(default)]
+    lok: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LokomotiveFile {
+    lokomotive: Vec<Lokomotive>,
+}
+
+impl Header for LokomotiveFile {
+    const HEADER: Option<&'static str> = Some("lokomotive");
+}
+
+#[test]
+fn macro_expressions_and_lists() {
+    let names = ["BR 103", "BR 110"];
+    let value = cs2! {
+        [lokomotive]
+        lokomotive { name: (names[0]), uid: 0x4007, mfxuid: 0x7fff8d2a }
+        lokomotive {
+            name: (format!("{} + {}", names[0], names[1])),
+            uid: (format!("0x{:x}", 0x4008)),
+            lok [ (names[0]), (names[1]), "BR 218" ],
+        }
+    };
+
+    let file: File<LokomotiveFile> = serde_cs2::from_value(value.clone()).unwrap();
+    let lokomotive = &file.body().lokomotive;
+    assert_eq!(lokomotive[0].name, "BR 103");
+    assert_eq!(lokomotive[0].mfxuid, Some(0x7fff8d2a));
+    assert_eq!(lokomotive[1].uid, 0x4008);
+    assert_eq!(lokomotive[1].lok, vec!["BR 103", "BR 110", "BR 218"]);
+
+    assert_eq!(
+        serde_cs2::to_string(&value["[lokomotive]"]).unwrap(),
+        "lokomotive\n .name=BR 103\n .uid=0x4007\n .mfxuid=0x7fff8d2a\nlokomotive\n .name=BR 103 + BR 110\n .uid=0x4008\n .lok=BR 103\n .lok=BR 110\n .lok=BR 218\n"
+    );
+    assert_eq!(cs2! {}, Value::default());
+}
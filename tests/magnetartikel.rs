@@ -0,0 +1,102 @@
+use serde_cs2::models::{Artikel, ArtikelTyp, MagnetartikelFile, Stellung, Version};
+use serde_cs2::File;
+
+fn weiche(id: u16, typ: ArtikelTyp, stellung: Stellung) -> Artikel {
+    Artikel {
+        id,
+        name: Some(format!("W{}", id)),
+        typ: Some(typ),
+        stellung: Some(stellung),
+        schaltzeit: Some(200),
+        dectyp: Some("mm2".to_owned()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn magnetartikel_serialize() {
+    let file = File::new(
+        "magnetartikel",
+        MagnetartikelFile {
+            version: Some(Version { minor: Some(1), ..Default::default() }),
+            artikel: vec![
+                weiche(1, ArtikelTyp::Linksweiche, Stellung::Rot),
+                weiche(2, ArtikelTyp::Dreiwegweiche, Stellung::Gelb),
+                Artikel {
+                    id: 3,
+                    typ: Some(ArtikelTyp::LichtsignalHp012Sh01),
+                    dectyp: Some("dcc".to_owned()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        },
+    );
+
+    let cs2 = r#"[magnetartikel]
+version
+ .minor=1
+artikel
+ .id=1
+ .name=W1
+ .typ=linksweiche
+ .stellung=0
+ .schaltzeit=200
+ .dectyp=mm2
+artikel
+ .id=2
+ .name=W2
+ .typ=dreiwegweiche
+ .stellung=2
+ .schaltzeit=200
+ .dectyp=mm2
+artikel
+ .id=3
+ .typ=lichtsignal_HP012_SH01
+ .dectyp=dcc
+"#;
+    assert_eq!(cs2, serde_cs2::to_string(&file).unwrap());
+    assert_eq!(file, serde_cs2::from_str(cs2).unwrap());
+}
+
+#[test]
+fn magnetartikel_deserialize() {
+    let cs2 = r#"[magnetartikel]
+version
+ .minor=1
+artikel
+ .id=17
+ .name=Einfahrt
+ .typ=formsignal_HP01
+ .stellung=1
+ .schaltzeit=200
+ .dectyp=mm2
+ .ungerade=1
+artikel
+ .id=18
+ .typ=neues_signal
+"#;
+    let file: File<MagnetartikelFile> = serde_cs2::from_str(cs2).unwrap();
+    let artikel = &file.body().artikel;
+    assert_eq!(artikel.len(), 2);
+    assert_eq!(artikel[0].typ, Some(ArtikelTyp::FormsignalHp01));
+    assert!(artikel[0].typ.as_ref().unwrap().is_signal());
+    assert_eq!(artikel[0].unknown.get("ungerade").and_then(|value| value.as_str()), Some("1"));
+    assert_eq!(artikel[1].typ, Some(ArtikelTyp::Other("neues_signal".to_owned())));
+    assert_eq!(artikel[0].stellung, Some(Stellung::Gruen));
+    assert_eq!(artikel[1].stellung, None);
+    assert_eq!(file.body().get(18), Some(&artikel[1]));
+
+    assert_eq!(cs2, serde_cs2::to_string(&file).unwrap());
+}
+
+#[test]
+fn magnetartikel_typ() {
+    for name in ["rechtsweiche", "k84_doppelausgang", "lichtsignal_SH01", "unbekannt"] {
+        let typ: ArtikelTyp = name.parse().unwrap();
+        assert_eq!(typ.as_str(), name);
+        assert_eq!(typ.to_string(), name);
+    }
+    assert!(ArtikelTyp::YWeiche.is_weiche());
+    assert!(!ArtikelTyp::StdRotGruen.is_signal());
+}
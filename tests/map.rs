@@ -0,0 +1,81 @@
+use serde_cs2::{File, Header, Map, Value};
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Lokomotive {
+    name: String,
+    #[serde(rename = "mfxAdr")]
+    mfx_adr: Map,
+    #[serde(flatten)]
+    unknown: Map,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct LokomotiveFile {
+    lokomotive: Vec<Lokomotive>,
+}
+
+impl Header for LokomotiveFile {
+    const HEADER: Option<&'static str> = Some("lokomotive");
+}
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+lokomotive
+ .name=BR 218
+ .mfxAdr
+ ..target=0
+ ..name=0x4006
+ ..addr=0
+ ..xcel=1
+ ..speedtable=1
+ ..xcel=2
+ .vmax=255
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=1
+ .tachomax=320
+"#;
+
+#[test]
+fn map_in_struct() {
+    let file: File<LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let lokomotive = &file.body().lokomotive[0];
+
+    let keys: Vec<&str> = lokomotive.mfx_adr.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["target", "name", "addr", "xcel", "speedtable", "xcel"]);
+    assert_eq!(lokomotive.mfx_adr["name"], Value::from("0x4006"));
+
+    let keys: Vec<&str> = lokomotive.unknown.iter().map(|(key, _)| key.as_str()).collect();
+    assert_eq!(keys, ["vmax", "funktionen", "funktionen", "tachomax"]);
+    assert_eq!(lokomotive.unknown.get_all("funktionen").nth(1).unwrap()["nr"], Value::from("1"));
+
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), LOKOMOTIVE_CS2);
+}
+
+#[test]
+fn map_round_trip() {
+    let cs2 = "b=1\na\n .y=2\n .x=1\nb=2\na\n .z=3\n";
+    let map: Map = serde_cs2::from_str(cs2).unwrap();
+    assert_eq!(map.len(), 4);
+    assert_eq!(map.get_all("b").collect::<Vec<_>>(), [&Value::from("1"), &Value::from("2")]);
+    assert_eq!(serde_cs2::to_string(&map).unwrap(), cs2);
+
+    let value = serde_cs2::to_value(&map).unwrap();
+    assert_eq!(serde_cs2::from_value::<Map>(value.clone()).unwrap(), map);
+    assert_eq!(Value::from(map), value);
+}
+
+#[test]
+fn map_edit() {
+    let mut map: Map = [("name", "Lok"), ("lok", "A")].into_iter().collect();
+    assert_eq!(map.set("name", "BR 218"), Some(Value::from("Lok")));
+    assert_eq!(map.set("vmax", "255"), None);
+    map.insert(1, "lok", "B");
+    map.push("lok", "C");
+    assert_eq!(map.remove("vmax"), Some(Value::from("255")));
+    map.retain(|key, value| key != "lok" || value.as_str() != Some("A"));
+    assert!(map.contains_key("lok"));
+    assert_eq!(serde_cs2::to_string(&map).unwrap(), "name=BR 218\nlok=B\nlok=C\n");
+}
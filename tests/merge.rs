@@ -0,0 +1,163 @@
+use serde_cs2::{Conflict, Identity, Value};
+
+const BASE: &str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .adresse=0x6
+ .vmax=140
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=1
+ ..typ=2
+lokomotive
+ .name=BR 103
+ .uid=0x4007
+ .adresse=0x7
+lokomotive
+ .name=V 60
+ .uid=0x4008
+"#;
+
+const OURS: &str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .adresse=0x16
+ .vmax=140
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=1
+ ..typ=3
+lokomotive
+ .name=BR 103
+ .uid=0x4007
+ .adresse=0x8
+lokomotive
+ .name=BR 120
+ .uid=0x4009
+"#;
+
+const THEIRS: &str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 103
+ .uid=0x4007
+ .adresse=0x9
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .adresse=0x6
+ .vmax=160
+ .funktionen
+ ..nr=1
+ ..typ=2
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=2
+ ..typ=7
+lokomotive
+ .name=V 60
+ .uid=0x4008
+lokomotive
+ .name=E 94
+ .uid=0x400a
+"#;
+
+#[test]
+fn merge_three_way() {
+    let merge = serde_cs2::merge_str(BASE, OURS, THEIRS).unwrap();
+
+    assert_eq!(
+        serde_cs2::to_string(merge.value()).unwrap(),
+        r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .adresse=0x16
+ .vmax=160
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=1
+ ..typ=3
+ .funktionen
+ ..nr=2
+ ..typ=7
+lokomotive
+ .name=BR 103
+ .uid=0x4007
+ .adresse=0x8
+lokomotive
+ .name=BR 120
+ .uid=0x4009
+lokomotive
+ .name=E 94
+ .uid=0x400a
+"#
+    );
+    assert_eq!(
+        merge.conflicts(),
+        &[Conflict {
+            path: "lokomotive[uid=0x4007].adresse".to_owned(),
+            base: Some("0x7".into()),
+            ours: Some("0x8".into()),
+            theirs: Some("0x9".into()),
+        }]
+    );
+}
+
+#[test]
+fn merge_removed_and_changed() {
+    let base = "lokomotive\n .name=Lok\n .adresse=0x5\nlokomotive\n .name=Lok 2\n";
+    let ours = "lokomotive\n .name=Lok 2\n";
+    let theirs = "lokomotive\n .name=Lok\n .adresse=0x6\nlokomotive\n .name=Lok 2\n";
+
+    let merge = serde_cs2::merge_str(base, ours, theirs).unwrap();
+    assert!(!merge.is_clean());
+    let conflict = &merge.conflicts()[0];
+    assert_eq!(conflict.path, "lokomotive[name=Lok]");
+    assert_eq!(conflict.ours, None);
+    assert_eq!(conflict.theirs.as_ref().unwrap()["adresse"].as_str(), Some("0x6"));
+    assert_eq!(
+        serde_cs2::to_string(merge.value()).unwrap(),
+        "lokomotive\n .name=Lok 2\n"
+    );
+
+    let theirs = "lokomotive\n .name=Lok\n .adresse=0x5\nlokomotive\n .name=Lok 2\n .vmax=80\n";
+    let merge = serde_cs2::merge_str(base, ours, theirs).unwrap();
+    assert!(merge.is_clean());
+    assert_eq!(
+        serde_cs2::to_string(merge.value()).unwrap(),
+        "lokomotive\n .name=Lok 2\n .vmax=80\n"
+    );
+}
+
+#[test]
+fn merge_added_on_both_sides() {
+    let base: Value = serde_cs2::from_str("traktion\n .lok=A\n").unwrap();
+    let ours: Value = serde_cs2::from_str("traktion\n .lok=A\n .lok=B\nlok\n .nr=1\n .x=1\n").unwrap();
+    let theirs: Value = serde_cs2::from_str("traktion\n .lok=A\n .lok=C\nlok\n .nr=1\n .x=2\n").unwrap();
+
+    let merge = serde_cs2::merge_with(&base, &ours, &theirs, &Identity::new(["nr"]));
+    let paths: Vec<_> = merge.conflicts().iter().map(|c| c.path.as_str()).collect();
+    assert_eq!(paths, vec!["traktion.lok", "lok[nr=1].x"]);
+    assert_eq!(
+        serde_cs2::to_string(merge.value()).unwrap(),
+        "traktion\n .lok=A\n .lok=B\nlok\n .nr=1\n .x=1\n"
+    );
+}
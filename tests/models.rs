@@ -0,0 +1,230 @@
+use serde_cs2::models::{
+    FunctionSymbol, Funktion, GeraetFile, Lokomotive, LokomotiveFile, MagnetartikelFile, Version,
+};
+use serde_cs2::{Error, File, Value};
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+version
+ .minor=3
+session
+ .id=1
+lokomotive
+ .uid=0x4006
+ .name=BR 218
+ .adresse=0x6
+ .typ=mfx
+ .sid=0x6
+ .mfxuid=0x7fff8d2a
+ .icon=DB BR 218
+ .symbol=2
+ .av=60
+ .bv=40
+ .volume=100
+ .progmask=0x0
+ .velocity=0
+ .richtung=0
+ .tachomax=200
+ .vmax=60
+ .vmin=3
+ .xprot=0
+ .mfxtyp=0
+ .blocks=0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+ .mfxAdr
+ ..target=0
+ ..name=0x4006
+ ..addr=0x14
+ ..xcel=0x17
+ .funktionen
+ ..nr=0
+ ..typ=1
+ ..dauer=0
+ ..wert=0
+ .funktionen
+ ..nr=1
+ ..typ=130
+ ..dauer=-1
+ ..wert=1
+ ..neu=1
+ .funktionen_2
+ ..nr=16
+ ..typ=51
+ .spm=1
+ .ft=0
+lokomotive
+ .uid=0xc005
+ .name=V 200
+ .adresse=0x5
+ .typ=dcc
+ .funktionen
+ ..nr=0
+traktion
+ .name=Doppel
+ .lok=BR 218
+ .lok=V 200
+"#;
+
+#[test]
+fn models_lokomotive_round_trip() {
+    let file: File<LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let body = file.body();
+    assert_eq!(body.version.as_ref().and_then(|version| version.minor), Some(3));
+    assert_eq!(body.session.as_ref().map(|session| session.id), Some(1));
+
+    let lok = &body.lokomotive[0];
+    assert_eq!(lok.uid, Some(0x4006));
+    assert_eq!(lok.adresse, Some(6));
+    assert_eq!(lok.mfxuid, Some(0x7fff8d2a));
+    assert_eq!(lok.blocks.map(|blocks| blocks[15]), Some(15));
+    assert_eq!(lok.mfx_adr.as_ref().unwrap()["addr"], Value::from("0x14"));
+    assert_eq!(lok.funktionen[1].dauer, Some(-1));
+    assert_eq!(lok.funktionen[1].unknown["neu"], Value::from("1"));
+    assert_eq!(lok.funktionen_2[0].nr, 16);
+    assert_eq!(lok.unknown.len(), 2);
+
+    let lok = &body.lokomotive[1];
+    assert_eq!(lok.funktionen.len(), 1);
+    assert_eq!(lok.mfxuid, None);
+
+    assert_eq!(body.traktion[0].lok.len(), 2);
+    assert!(!body.unknown.contains_key("traktion"));
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), LOKOMOTIVE_CS2);
+}
+
+#[test]
+fn models_lokomotive_new() {
+    let body = LokomotiveFile {
+        version: Some(Version { minor: Some(3), ..Default::default() }),
+        lokomotive: vec![Lokomotive {
+            uid: Some(0x12),
+            name: "Lok".to_owned(),
+            adresse: Some(0x12),
+            funktionen: vec![Funktion { nr: 0, typ: Some(1), ..Default::default() }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let file = File::new("lokomotive", body);
+    let cs2 = serde_cs2::to_string(&file).unwrap();
+    assert_eq!(
+        cs2,
+        "[lokomotive]\nversion\n .minor=3\nlokomotive\n .uid=0x12\n .name=Lok\n .adresse=0x12\n .funktionen\n ..nr=0\n ..typ=1\n"
+    );
+    assert_eq!(serde_cs2::from_str::<File<LokomotiveFile>>(&cs2).unwrap(), file);
+}
+
+// Hexadecimal values are written without leading zeros, like the Central
+// Station writes the uid of a lokomotive without mfx decoder.
+#[test]
+fn models_lokomotive_short_hex() {
+    let cs2 = "[lokomotive]\nlokomotive\n .uid=0x6\n .name=BR 89\n .adresse=0x6\n";
+    let file: File<LokomotiveFile> = serde_cs2::from_str(cs2).unwrap();
+    assert_eq!(file.body().lokomotive[0].uid, Some(6));
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), cs2);
+
+    let padded = "[lokomotive]\nlokomotive\n .uid=0x0006\n .name=BR 89\n .adresse=0x6\n";
+    let file: File<LokomotiveFile> = serde_cs2::from_str(padded).unwrap();
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), cs2);
+}
+
+// A single lokomotive is buffered as lone block by the flattened file, its
+// functions have to be grouped again.
+#[test]
+fn models_lokomotive_single() {
+    let cs2 = "[lokomotive]\nlokomotive\n .name=Lok\n .funktionen\n ..nr=0\n .funktionen\n ..nr=1\n .spm=1\n";
+    let file: File<LokomotiveFile> = serde_cs2::from_str(cs2).unwrap();
+    let lok = &file.body().lokomotive[0];
+    assert_eq!(lok.funktionen.len(), 2);
+    assert_eq!(lok.funktionen[1].nr, 1);
+    assert!(lok.unknown.contains_key("spm"));
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), cs2);
+}
+
+#[test]
+fn models_lokomotive_errors() {
+    let cs2 = "[lokomotive]\nlokomotive\n .name=Lok\n .uid=4006\n";
+    assert!(serde_cs2::from_str::<File<LokomotiveFile>>(cs2).is_err());
+    let cs2 = "[lokomotive]\nlokomotive\n .name=Lok\n .blocks=0 1 2\n";
+    assert!(serde_cs2::from_str::<File<LokomotiveFile>>(cs2).is_err());
+}
+
+#[test]
+fn models_wrong_header() {
+    let cs2 = "[magnetartikel]\nversion\n .minor=1\nartikel\n .id=1\n";
+    match serde_cs2::from_str::<File<LokomotiveFile>>(cs2) {
+        Err(Error::WrongHeader { expected, found }) => {
+            assert_eq!(expected, "lokomotive");
+            assert_eq!(found, "magnetartikel");
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+    assert!(serde_cs2::from_str::<File<MagnetartikelFile>>(cs2).is_ok());
+
+    let value: Value = serde_cs2::from_str(cs2).unwrap();
+    assert!(matches!(
+        serde_cs2::from_value::<File<LokomotiveFile>>(value.clone()),
+        Err(Error::WrongHeader { .. })
+    ));
+    assert!(serde_cs2::from_value::<File<MagnetartikelFile>>(value).is_ok());
+
+    let body: LokomotiveFile = serde_cs2::from_str("version\n .minor=1\n").unwrap();
+    assert_eq!(File::from_body(body).header(), "lokomotive");
+}
+
+const GERAET_VRS: &str = "[geraet]\r\nversion\r\n .minor=1\r\ngeraet\r\n .sernum=006123\r\n .articleno=60215\r\n .produzent=Maerklin\r\n .produktname=Central Station 2\r\n .hardvers=HW:03.04\r\n .softvers=4.2.13\r\n .gfpuid=a1b2c3d4\r\n .guiuid=a1b2c3d5\r\n .linkvers=1.0\r\n";
+
+#[test]
+fn models_geraet() {
+    let file: File<GeraetFile> = serde_cs2::from_str(GERAET_VRS).unwrap();
+    assert_eq!(file.header(), "geraet");
+    let geraet = file.body().geraet.as_ref().unwrap();
+    assert_eq!(geraet.sernum.as_deref(), Some("006123"));
+    assert_eq!(geraet.articleno.as_deref(), Some("60215"));
+    assert_eq!(geraet.softvers.as_deref(), Some("4.2.13"));
+    assert_eq!(geraet.gfpuid.as_deref(), Some("a1b2c3d4"));
+    assert_eq!(geraet.unknown["linkvers"], Value::from("1.0"));
+
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), GERAET_VRS.replace('\r', ""));
+
+    let lokomotive = "[lokomotive]\r\nversion\r\n .minor=3\r\n";
+    assert!(matches!(
+        serde_cs2::from_str::<File<GeraetFile>>(lokomotive),
+        Err(Error::WrongHeader { .. })
+    ));
+}
+
+#[test]
+fn models_function_symbol() {
+    let file: File<LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let lok = &file.body().lokomotive[0];
+
+    let licht = lok.funktion(0).unwrap();
+    assert_eq!(licht.symbol(), Some(FunctionSymbol::Licht));
+    assert!(!licht.is_momentary());
+
+    let momentary = lok.funktion(1).unwrap();
+    assert_eq!(momentary.typ, Some(130));
+    assert_eq!(momentary.symbol(), Some(FunctionSymbol::Innenbeleuchtung));
+    assert!(momentary.is_momentary());
+
+    let cs3 = lok.funktion(16).unwrap();
+    assert_eq!(cs3.symbol(), Some(FunctionSymbol::Other(51)));
+    assert_eq!(cs3.symbol().unwrap().name_en(), None);
+    assert!(lok.funktion(17).is_none());
+
+    assert_eq!(FunctionSymbol::Horn.name_de(), Some("Horn"));
+    assert_eq!(FunctionSymbol::Rauch.name_en(), Some("smoke generator"));
+    for typ in 0..=0xff {
+        let (symbol, momentary) = FunctionSymbol::from_raw(typ);
+        assert_eq!(symbol.to_raw(momentary), typ);
+    }
+    assert_eq!(FunctionSymbol::from(138), FunctionSymbol::Horn);
+    assert_eq!(FunctionSymbol::Other(10), FunctionSymbol::Horn);
+    assert_eq!(FunctionSymbol::Other(10).name_de(), Some("Horn"));
+    assert_eq!(FunctionSymbol::Other(128 + 51), FunctionSymbol::Other(51));
+    assert_eq!(FunctionSymbol::Other(128 + 51).to_raw(false), 51);
+    assert_eq!(FunctionSymbol::from_raw(FunctionSymbol::Other(138).to_raw(true)), (FunctionSymbol::Horn, true));
+
+    let mut funktion = Funktion::default();
+    funktion.set_symbol(FunctionSymbol::Horn, true);
+    assert_eq!(funktion.typ, Some(138));
+}
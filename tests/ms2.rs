@@ -0,0 +1,91 @@
+use serde_cs2::models::{
+    from_ms2_stream, to_ms2_stream, Funktion, LokinfoFile, Lokinfo, LoklisteFile, Lokomotive, LokomotiveFile, Protocol,
+};
+use serde_cs2::File;
+
+const LOKLISTE: &str = "[lokliste]\nlok\n .nr=2\n .name=BR 218\nlok\n .nr=3\n .name=V 200\nnumloks\n .wert=12\n";
+
+const LOKINFO: &str = r#"[lokomotive]
+lok
+ .uid=0x4006
+ .name=BR 218
+ .adresse=0x6
+ .typ=mfx
+ .mfxuid=0x7fff8d2a
+ .av=60
+ .bv=40
+ .volume=100
+ .velocity=0
+ .richtung=0
+ .vmax=60
+ .vmin=3
+ .fkt
+ ..typ=1
+ ..wert=1
+ .fkt
+ ..typ=138
+ ..wert=0
+"#;
+
+#[test]
+fn ms2_lokliste() {
+    let mut stream = LOKLISTE.as_bytes().to_vec();
+    stream.resize(80, 0);
+    let file: File<LoklisteFile> = from_ms2_stream(&stream).unwrap();
+    assert_eq!(file.header(), "lokliste");
+    let body = file.body();
+    assert_eq!(body.lok.len(), 2);
+    assert_eq!(body.lok[1].nr, 3);
+    assert_eq!(body.lok[1].name, "V 200");
+    assert_eq!(body.numloks.as_ref().map(|numloks| numloks.wert), Some(12));
+
+    let stream = to_ms2_stream(&file).unwrap();
+    assert_eq!(stream.len() % 8, 0);
+    assert!(stream.starts_with(LOKLISTE.as_bytes()));
+    assert!(stream[LOKLISTE.len()..].iter().all(|byte| *byte == 0));
+}
+
+#[test]
+fn ms2_lokinfo() {
+    let file: File<LokinfoFile> = from_ms2_stream(LOKINFO.as_bytes()).unwrap();
+    assert_eq!(file.header(), "lokomotive");
+    let lok = file.body().lok.as_ref().unwrap();
+    assert_eq!(lok.uid, Some(0x4006));
+    assert_eq!(lok.typ, Some(Protocol::Mfx));
+    assert_eq!(lok.fkt.len(), 2);
+    assert_eq!(lok.fkt[1].typ, Some(138));
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), LOKINFO);
+
+    let single = "[lokomotive]\nlok\n .name=V 200\n .fkt\n ..typ=1\n";
+    let file: File<LokinfoFile> = serde_cs2::from_str(single).unwrap();
+    assert_eq!(file.body().lok.as_ref().unwrap().fkt.len(), 1);
+}
+
+#[test]
+fn ms2_emulate() {
+    let lokomotive = LokomotiveFile {
+        lokomotive: ["BR 01", "BR 218", "V 200"]
+            .iter()
+            .map(|name| Lokomotive {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let lokliste = LoklisteFile::page(&lokomotive, 1, 5);
+    let names: Vec<_> = lokliste.lok.iter().map(|lok| (lok.nr, lok.name.as_str())).collect();
+    assert_eq!(names, vec![(1, "BR 218"), (2, "V 200")]);
+    assert_eq!(lokliste.numloks.unwrap().wert, 3);
+
+    let lok = Lokomotive {
+        name: "BR 218".to_owned(),
+        funktionen: vec![Funktion { nr: 0, typ: Some(1), wert: Some(1), ..Default::default() }],
+        funktionen_2: vec![Funktion { nr: 16, typ: Some(10), ..Default::default() }],
+        ..Default::default()
+    };
+    let lokinfo = Lokinfo::from(&lok);
+    assert_eq!(lokinfo.fkt.len(), usize::from(Lokinfo::FUNKTIONEN));
+    assert_eq!((lokinfo.fkt[0].typ, lokinfo.fkt[0].wert), (Some(1), Some(1)));
+    assert_eq!((lokinfo.fkt[1].typ, lokinfo.fkt[1].wert), (Some(0), Some(0)));
+}
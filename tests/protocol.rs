@@ -0,0 +1,89 @@
+use serde_cs2::models::{AddressError, AddressProblem, LokomotiveFile, Protocol};
+use serde_cs2::File;
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+lokomotive
+ .uid=0x4006
+ .name=BR 218
+ .adresse=0x6
+ .typ=mfx
+ .sid=0x6
+ .mfxuid=0x7fff8d2a
+lokomotive
+ .uid=0xc005
+ .name=V 200
+ .adresse=0x5
+ .typ=dcc
+lokomotive
+ .uid=0x50
+ .name=BR 89
+ .adresse=0x50
+ .typ=mm2_dil8
+lokomotive
+ .uid=0x812
+ .name=E 94
+ .adresse=0x12
+ .typ=sx1
+lokomotive
+ .name=Doppel
+ .typ=traktion
+"#;
+
+#[test]
+fn protocol_parse() {
+    let file: File<LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let protocols: Vec<_> = file.body().lokomotive.iter().map(|lok| lok.typ.clone().unwrap()).collect();
+    assert_eq!(
+        protocols,
+        vec![
+            Protocol::Mfx,
+            Protocol::Dcc,
+            Protocol::Mm2Dil8,
+            Protocol::Sx1,
+            Protocol::Other("traktion".to_owned())
+        ]
+    );
+    assert_eq!(Protocol::Mm2Prg.to_string(), "mm2_prg");
+    assert_eq!(Protocol::Sx1.uid(0x12), Some(0x812));
+    assert_eq!(Protocol::Dcc.uid(0x4000), None);
+    assert_eq!(Protocol::Other("traktion".to_owned()).uid(1), None);
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), LOKOMOTIVE_CS2);
+}
+
+#[test]
+fn protocol_valid() {
+    let file: File<LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    assert_eq!(file.body().validate_adressen(), Ok(()));
+}
+
+#[test]
+fn protocol_problems() {
+    let mut file: File<LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let body = file.body_mut();
+    body.lokomotive[0].sid = Some(0x7);
+    body.lokomotive[0].mfxuid = None;
+    body.lokomotive[1].uid = Some(0x4005);
+    body.lokomotive[1].mfxuid = Some(0x1234);
+    body.lokomotive[2].adresse = Some(81);
+    body.lokomotive[3].adresse = None;
+
+    let errors = file.body().validate_adressen().unwrap_err();
+    let error = |nr: usize, name: &str, problem| AddressError {
+        nr,
+        name: name.to_owned(),
+        problem,
+    };
+    assert_eq!(
+        errors,
+        vec![
+            error(0, "BR 218", AddressProblem::WrongSid { expected: 6, found: 7 }),
+            error(0, "BR 218", AddressProblem::MissingMfxuid),
+            error(1, "V 200", AddressProblem::WrongUid { expected: 0xc005, found: 0x4005 }),
+            error(1, "V 200", AddressProblem::UnexpectedMfxuid),
+            error(2, "BR 89", AddressProblem::AdresseOutOfRange { adresse: 81, adressen: 1..=80 }),
+            error(3, "E 94", AddressProblem::MissingAdresse),
+        ]
+    );
+    assert_eq!(errors[2].to_string(), "lokomotive 1 `V 200`: expected uid 0xc005, found 0x4005");
+    assert_eq!(errors[4].to_string(), "lokomotive 2 `BR 89`: adresse 81 out of range 1..=80");
+}
@@ -0,0 +1,89 @@
+use serde_cs2::{Error, Position, Query};
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=3
+ ..typ=131
+lokomotive
+ .name=BR 103
+ .mfxuid=0x7fff8d2a
+ .funktionen
+ ..nr=3
+ ..typ=5
+"#;
+
+#[test]
+fn query_select() {
+    let typ = serde_cs2::select(
+        LOKOMOTIVE_CS2,
+        r#"lokomotive[name="BR 218"].funktionen[nr=3].typ"#,
+    )
+    .unwrap();
+    assert_eq!(typ.len(), 1);
+    assert_eq!(typ[0].key(), "typ");
+    assert_eq!(typ[0].value(), Some("131"));
+    assert_eq!(
+        typ[0].position(),
+        Position { line: 12, column: 4, offset: 123 }
+    );
+    assert_eq!(typ[0].value_position().unwrap().column, 8);
+    assert_eq!(&LOKOMOTIVE_CS2[typ[0].value_position().unwrap().offset..][..3], "131");
+
+    let typen = serde_cs2::select(LOKOMOTIVE_CS2, "lokomotive.funktionen[nr=3].typ").unwrap();
+    let typen: Vec<_> = typen.iter().map(|typ| typ.value().unwrap()).collect();
+    assert_eq!(typen, vec!["131", "5"]);
+
+    let lok = serde_cs2::select(LOKOMOTIVE_CS2, "lokomotive[mfxuid].name").unwrap();
+    assert_eq!(lok[0].value(), Some("BR 103"));
+
+    let lok = serde_cs2::select(LOKOMOTIVE_CS2, "lokomotive[1]").unwrap();
+    assert_eq!(lok[0].value(), None);
+    assert_eq!(lok[0].position().line, 13);
+
+    let typen = serde_cs2::select(LOKOMOTIVE_CS2, "lokomotive.funktionen[0].typ").unwrap();
+    let typen: Vec<_> = typen.iter().map(|typ| typ.value().unwrap()).collect();
+    assert_eq!(typen, vec!["1", "5"]);
+
+    let all = serde_cs2::select(LOKOMOTIVE_CS2, "*").unwrap();
+    assert_eq!(all.len(), 3);
+    let names = serde_cs2::select(LOKOMOTIVE_CS2, "lokomotive[name=BR 103].*").unwrap();
+    assert_eq!(names.len(), 3);
+
+    let none = serde_cs2::select(LOKOMOTIVE_CS2, "lokomotive[name=BR 120].uid").unwrap();
+    assert!(none.is_empty());
+}
+
+#[test]
+fn query_reuse() {
+    let query: Query = "lokomotive.name".parse().unwrap();
+    let second = "lokomotive\n .name=Lok\n";
+    assert_eq!(query.select(LOKOMOTIVE_CS2).unwrap().len(), 2);
+    assert_eq!(query.select(second).unwrap()[0].value(), Some("Lok"));
+}
+
+#[test]
+fn query_invalid_path() {
+    for (path, position) in [
+        ("", 0),
+        ("lokomotive.", 11),
+        ("lokomotive[name=\"BR 218]", 11),
+        ("lokomotive[]", 11),
+        ("lokomotive[nr=3]x", 16),
+    ] {
+        assert_eq!(
+            path.parse::<Query>(),
+            Err(Error::InvalidPath {
+                path: path.to_owned(),
+                position
+            })
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use serde_cs2::{File, Schema};
+
+// The types generated from `LOKOMOTIVE_CS2`.
+mod generated {
+    include!("schema/lokomotive.rs");
+}
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=BR 218
+ .uid=0x4001
+ .mfxuid=0x7fff8d2a
+ .adresse=0x5
+ .typ=mm2_prg
+ .vmax=255
+ .blocks=0 1 2 3
+ .mfxAdr
+ ..target=0
+ ..name=1
+ .funktionen
+ ..nr=0
+ ..typ=1
+ ..dauer=-1
+ .funktionen
+ ..nr=1
+lokomotive
+ .name=BR 103
+ .uid=0x4002
+ .adresse=0x12
+ .typ=mfx
+ .vmax=1000
+ .blocks=0 1 2 300
+ .funktionen
+ ..nr=0
+ ..typ=130
+traktion
+ .name=A
+ .lok=BR 218
+ .lok=BR 103
+"#;
+
+#[test]
+fn schema_to_rust() {
+    let mut schema = Schema::new();
+    schema.add(LOKOMOTIVE_CS2).unwrap();
+    assert_eq!(schema.header(), Some("lokomotive"));
+    assert_eq!(schema.to_rust("LokomotiveFile"), include_str!("schema/lokomotive.rs"));
+}
+
+#[test]
+fn schema_generated_round_trip() {
+    let file: File<generated::LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let lokomotive = &file.body().lokomotive;
+    assert_eq!(lokomotive[0].mfxuid, Some(0x7fff8d2a));
+    assert_eq!(lokomotive[1].blocks, [0, 1, 2, 300]);
+    assert_eq!(lokomotive[0].funktionen[0].dauer, Some(-1));
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), LOKOMOTIVE_CS2);
+}
+
+#[test]
+fn schema_samples() {
+    let mut schema = Schema::new();
+    schema.add("a\n .n=1\n .x=0x01\n .s=1 2\n .k=1\n .k=2\n").unwrap();
+    schema.add("a\n .n=-300\n .x=0x0f\n .s=1 2 3\n .t=007\n").unwrap();
+    assert_eq!(
+        schema.to_rust("Sample"),
+        r#"use serde::{Deserialize, Serialize};
+use serde_hex::{SerHex, StrictPfx};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct A {
+    pub n: i16,
+    #[serde(with = "SerHex::<StrictPfx>")]
+    pub x: u8,
+    pub s: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub k: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub t: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Sample {
+    pub a: A,
+}
+"#
+    );
+
+    assert!(matches!(
+        schema.add("[gleisbild]\n"),
+        Err(serde_cs2::Error::UnexpectedHeader)
+    ));
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use serde_hex::{CompactPfx, SerHex, SerHexOpt, StrictPfx};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Version {
+    pub minor: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct MfxAdr {
+    pub target: u8,
+    pub name: u8,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Funktionen {
+    pub nr: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub typ: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dauer: Option<i8>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Lokomotive {
+    pub name: String,
+    #[serde(with = "SerHex::<StrictPfx>")]
+    pub uid: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "SerHexOpt::<StrictPfx>")]
+    pub mfxuid: Option<u32>,
+    #[serde(with = "SerHex::<CompactPfx>")]
+    pub adresse: u8,
+    pub typ: String,
+    pub vmax: u16,
+    pub blocks: [u16; 4],
+    #[serde(rename = "mfxAdr", default, skip_serializing_if = "Option::is_none")]
+    pub mfx_adr: Option<MfxAdr>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub funktionen: Vec<Funktionen>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Traktion {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lok: Vec<String>,
+}
+
+/// The entries of a `[lokomotive]` file, the body of
+/// `serde_cs2::File<LokomotiveFile>`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LokomotiveFile {
+    pub version: Version,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lokomotive: Vec<Lokomotive>,
+    pub traktion: Traktion,
+}
+
+impl serde_cs2::Header for LokomotiveFile {
+    const HEADER: Option<&'static str> = Some("lokomotive");
+}
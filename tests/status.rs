@@ -0,0 +1,443 @@
+use serde_cs2::models::{
+    FahrstrassenFile, FsstatFile, Lokstat, LokstatFile, LokomotiveFile, MagnetartikelFile, MagstatFile, Stellung,
+    Version,
+};
+use serde_cs2::File;
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+lokomotive
+ .uid=0x4006
+ .name=BR 218
+ .velocity=0
+ .richtung=0
+ .funktionen
+ ..nr=0
+ ..typ=1
+ ..wert=0
+ .funktionen
+ ..nr=1
+ ..typ=2
+lokomotive
+ .uid=0x4007
+ .name=BR 103
+"#;
+
+#[test]
+fn status_lokstat() {
+    let lokstat = Lokstat::new("01 133 DB");
+    assert_eq!(lokstat.funktionen.len(), 32);
+    let cs2 = serde_cs2::to_string(&File::new("lokomotive", LokstatFile {
+        lokomotive: vec![lokstat],
+        ..Default::default()
+    }))
+    .unwrap();
+    assert!(cs2.starts_with("[lokomotive]\nlokomotive\n .name=01 133 DB\n .velocity=0\n .richtung=0\n .funktionen\n ..nr=0\n .funktionen\n ..nr=1\n"));
+    assert!(cs2.ends_with(" .funktionen\n ..nr=31\n"));
+
+    let status: File<LokstatFile> = serde_cs2::from_str(&cs2).unwrap();
+    assert_eq!(serde_cs2::to_string(&status).unwrap(), cs2);
+}
+
+#[test]
+fn status_apply_lokstat() {
+    let definition: File<LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let mut lokomotive = definition.into_body();
+
+    let mut status = LokstatFile::from(&lokomotive);
+    assert_eq!(status.lokomotive.len(), 2);
+    assert_eq!(status.lokomotive[0].funktionen.len(), 32);
+    status.lokomotive[0].velocity = Some(120);
+    status.lokomotive[0].richtung = Some(1);
+    status.lokomotive[0].funktionen[1].wert = Some(1);
+    status.lokomotive.push(Lokstat::new("Unbekannt"));
+
+    lokomotive.apply_status(&status);
+    let lok = &lokomotive.lokomotive[0];
+    assert_eq!((lok.velocity, lok.richtung), (Some(120), Some(1)));
+    assert_eq!(lok.funktionen[0].wert, Some(0));
+    assert_eq!(lok.funktionen[1].wert, Some(1));
+    assert_eq!(lokomotive.lokomotive[1].velocity, Some(0));
+}
+
+#[test]
+fn status_apply_magstat_fsstat() {
+    let magnetartikel = "[magnetartikel]\nartikel\n .id=1\n .typ=linksweiche\n .stellung=0\nartikel\n .id=2\n .typ=rechtsweiche\n";
+    let magstat = "[magnetartikel]\nversion\n .minor=1\nartikel\n .id=2\n .stellung=1\nartikel\n .id=3\n .stellung=0\n";
+    let mut magnetartikel: File<MagnetartikelFile> = serde_cs2::from_str(magnetartikel).unwrap();
+    let magstat: File<MagstatFile> = serde_cs2::from_str(magstat).unwrap();
+    magnetartikel.body_mut().apply_status(magstat.body());
+    let artikel = &magnetartikel.body().artikel;
+    assert_eq!(artikel[0].stellung, Some(Stellung::Rot));
+    assert_eq!(artikel[1].stellung, Some(Stellung::Gruen));
+
+    let fahrstrassen = "[fahrstrassen]\nfahrstrasse\n .id=1\n .name=A\nfahrstrasse\n .id=2\n .name=B\n";
+    let fsstat = "[fahrstrassen]\nfahrstrasse\n .id=1\n .aktiv=0\nfahrstrasse\n .id=2\n .aktiv=1\n";
+    let fahrstrassen: File<FahrstrassenFile> = serde_cs2::from_str(fahrstrassen).unwrap();
+    let fsstat: File<FsstatFile> = serde_cs2::from_str(fsstat).unwrap();
+    let aktiv: Vec<_> = fahrstrassen.body().aktiv(fsstat.body()).map(|fahrstrasse| fahrstrasse.id).collect();
+    assert_eq!(aktiv, [2]);
+}
+
+fn new_lokstat(name: &str, velocity: u16, richtung: u8) -> Lokstat {
+    let mut lokstat = Lokstat::new(name);
+    lokstat.velocity = Some(velocity);
+    lokstat.richtung = Some(richtung);
+    lokstat.funktionen[0].wert = Some(1);
+    lokstat
+}
+
+fn lokstat_file(lokomotive: Vec<Lokstat>) -> LokstatFile {
+    LokstatFile {
+        version: Some(Version { minor: Some(3), ..Default::default() }),
+        lokomotive,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn status_lokstat_insert() {
+    let mut serialized = String::new();
+    serde_cs2::insert_into_string(&mut serialized, "lokomotive", &new_lokstat("01 133 DB", 255, 1)).unwrap();
+
+    let cs2_str = r#"lokomotive
+ .name=01 133 DB
+ .velocity=255
+ .richtung=1
+ .funktionen
+ ..nr=0
+ ..wert=1
+ .funktionen
+ ..nr=1
+ .funktionen
+ ..nr=2
+ .funktionen
+ ..nr=3
+ .funktionen
+ ..nr=4
+ .funktionen
+ ..nr=5
+ .funktionen
+ ..nr=6
+ .funktionen
+ ..nr=7
+ .funktionen
+ ..nr=8
+ .funktionen
+ ..nr=9
+ .funktionen
+ ..nr=10
+ .funktionen
+ ..nr=11
+ .funktionen
+ ..nr=12
+ .funktionen
+ ..nr=13
+ .funktionen
+ ..nr=14
+ .funktionen
+ ..nr=15
+ .funktionen
+ ..nr=16
+ .funktionen
+ ..nr=17
+ .funktionen
+ ..nr=18
+ .funktionen
+ ..nr=19
+ .funktionen
+ ..nr=20
+ .funktionen
+ ..nr=21
+ .funktionen
+ ..nr=22
+ .funktionen
+ ..nr=23
+ .funktionen
+ ..nr=24
+ .funktionen
+ ..nr=25
+ .funktionen
+ ..nr=26
+ .funktionen
+ ..nr=27
+ .funktionen
+ ..nr=28
+ .funktionen
+ ..nr=29
+ .funktionen
+ ..nr=30
+ .funktionen
+ ..nr=31
+"#;
+
+    assert_eq!(serialized, cs2_str);
+
+    let lokstat_file: LokstatFile = serde_cs2::from_str(serialized.as_str()).unwrap();
+    let lokstat = &lokstat_file.lokomotive[0];
+    assert_eq!(lokstat.richtung, Some(1));
+    assert_eq!(lokstat.velocity, Some(255));
+
+    for (index, function) in lokstat.funktionen.iter().enumerate() {
+        assert_eq!(function.nr, index as u8);
+        if index == 0 {
+            assert_eq!(function.wert, Some(1));
+        } else {
+            assert_eq!(function.wert, None);
+        }
+    }
+}
+
+#[test]
+fn status_lokstat_file_simple() {
+    let lokstat = lokstat_file(vec![new_lokstat("01 133 DB", 255, 1)]);
+    let serialized = serde_cs2::to_string(&File::from_body(lokstat)).unwrap();
+
+    let cs2_str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=01 133 DB
+ .velocity=255
+ .richtung=1
+ .funktionen
+ ..nr=0
+ ..wert=1
+ .funktionen
+ ..nr=1
+ .funktionen
+ ..nr=2
+ .funktionen
+ ..nr=3
+ .funktionen
+ ..nr=4
+ .funktionen
+ ..nr=5
+ .funktionen
+ ..nr=6
+ .funktionen
+ ..nr=7
+ .funktionen
+ ..nr=8
+ .funktionen
+ ..nr=9
+ .funktionen
+ ..nr=10
+ .funktionen
+ ..nr=11
+ .funktionen
+ ..nr=12
+ .funktionen
+ ..nr=13
+ .funktionen
+ ..nr=14
+ .funktionen
+ ..nr=15
+ .funktionen
+ ..nr=16
+ .funktionen
+ ..nr=17
+ .funktionen
+ ..nr=18
+ .funktionen
+ ..nr=19
+ .funktionen
+ ..nr=20
+ .funktionen
+ ..nr=21
+ .funktionen
+ ..nr=22
+ .funktionen
+ ..nr=23
+ .funktionen
+ ..nr=24
+ .funktionen
+ ..nr=25
+ .funktionen
+ ..nr=26
+ .funktionen
+ ..nr=27
+ .funktionen
+ ..nr=28
+ .funktionen
+ ..nr=29
+ .funktionen
+ ..nr=30
+ .funktionen
+ ..nr=31
+"#;
+
+    assert_eq!(serialized, cs2_str);
+
+    let lokstat_file: File<LokstatFile> = serde_cs2::from_str(serialized.as_str()).unwrap();
+    assert_eq!(lokstat_file.header(), "lokomotive");
+    let lokstat_file = lokstat_file.into_body();
+    assert_eq!(lokstat_file.lokomotive.len(), 1);
+
+    let lokstat = lokstat_file.lokomotive.first().unwrap();
+    assert_eq!(lokstat.richtung, Some(1));
+    assert_eq!(lokstat.velocity, Some(255));
+}
+
+#[test]
+fn status_lokstat_file_full() {
+    let lokstat = lokstat_file(vec![new_lokstat("01 133 DB", 255, 1), new_lokstat("02", 200, 0)]);
+    let serialized = serde_cs2::to_string(&File::from_body(lokstat)).unwrap();
+
+    let cs2_str = r#"[lokomotive]
+version
+ .minor=3
+lokomotive
+ .name=01 133 DB
+ .velocity=255
+ .richtung=1
+ .funktionen
+ ..nr=0
+ ..wert=1
+ .funktionen
+ ..nr=1
+ .funktionen
+ ..nr=2
+ .funktionen
+ ..nr=3
+ .funktionen
+ ..nr=4
+ .funktionen
+ ..nr=5
+ .funktionen
+ ..nr=6
+ .funktionen
+ ..nr=7
+ .funktionen
+ ..nr=8
+ .funktionen
+ ..nr=9
+ .funktionen
+ ..nr=10
+ .funktionen
+ ..nr=11
+ .funktionen
+ ..nr=12
+ .funktionen
+ ..nr=13
+ .funktionen
+ ..nr=14
+ .funktionen
+ ..nr=15
+ .funktionen
+ ..nr=16
+ .funktionen
+ ..nr=17
+ .funktionen
+ ..nr=18
+ .funktionen
+ ..nr=19
+ .funktionen
+ ..nr=20
+ .funktionen
+ ..nr=21
+ .funktionen
+ ..nr=22
+ .funktionen
+ ..nr=23
+ .funktionen
+ ..nr=24
+ .funktionen
+ ..nr=25
+ .funktionen
+ ..nr=26
+ .funktionen
+ ..nr=27
+ .funktionen
+ ..nr=28
+ .funktionen
+ ..nr=29
+ .funktionen
+ ..nr=30
+ .funktionen
+ ..nr=31
+lokomotive
+ .name=02
+ .velocity=200
+ .richtung=0
+ .funktionen
+ ..nr=0
+ ..wert=1
+ .funktionen
+ ..nr=1
+ .funktionen
+ ..nr=2
+ .funktionen
+ ..nr=3
+ .funktionen
+ ..nr=4
+ .funktionen
+ ..nr=5
+ .funktionen
+ ..nr=6
+ .funktionen
+ ..nr=7
+ .funktionen
+ ..nr=8
+ .funktionen
+ ..nr=9
+ .funktionen
+ ..nr=10
+ .funktionen
+ ..nr=11
+ .funktionen
+ ..nr=12
+ .funktionen
+ ..nr=13
+ .funktionen
+ ..nr=14
+ .funktionen
+ ..nr=15
+ .funktionen
+ ..nr=16
+ .funktionen
+ ..nr=17
+ .funktionen
+ ..nr=18
+ .funktionen
+ ..nr=19
+ .funktionen
+ ..nr=20
+ .funktionen
+ ..nr=21
+ .funktionen
+ ..nr=22
+ .funktionen
+ ..nr=23
+ .funktionen
+ ..nr=24
+ .funktionen
+ ..nr=25
+ .funktionen
+ ..nr=26
+ .funktionen
+ ..nr=27
+ .funktionen
+ ..nr=28
+ .funktionen
+ ..nr=29
+ .funktionen
+ ..nr=30
+ .funktionen
+ ..nr=31
+"#;
+
+    assert_eq!(serialized, cs2_str);
+
+    let lokstat_file: File<LokstatFile> = serde_cs2::from_str(serialized.as_str()).unwrap();
+    assert_eq!(lokstat_file.header(), "lokomotive");
+    let lokstat_file = lokstat_file.into_body();
+    assert_eq!(lokstat_file.lokomotive.len(), 2);
+
+    let lokstat = lokstat_file.lokomotive.first().unwrap();
+    assert_eq!(lokstat.richtung, Some(1));
+    assert_eq!(lokstat.velocity, Some(255));
+
+    let lokstat = lokstat_file.lokomotive.last().unwrap();
+    assert_eq!(lokstat.richtung, Some(0));
+    assert_eq!(lokstat.velocity, Some(200));
+}
@@ -0,0 +1,99 @@
+use serde_cs2::models::{LokomotiveFile, Member, Traktion};
+use serde_cs2::File;
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+lokomotive
+ .uid=0x4006
+ .name=BR 218
+lokomotive
+ .uid=0xc005
+ .name=V 200
+lokomotive
+ .name=BR 103
+traktion
+ .uid=0x4010
+ .name=Doppel
+ .lok
+ ..uid=0x4006
+ ..richtung=0
+ ..faktor=100
+ .lok
+ ..uid=0xc005
+ ..richtung=1
+ ..faktor=95
+ ..neu=1
+traktion
+ .name=Vorspann
+ .lok=BR 103
+ .lok=BR 218
+"#;
+
+#[test]
+fn traktion_round_trip() {
+    let file: File<LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let body = file.body();
+    assert_eq!(body.lokomotive.len(), 3);
+    assert_eq!(body.traktion.len(), 2);
+
+    let doppel = &body.traktion[0];
+    assert_eq!(doppel.uid, Some(0x4010));
+    assert_eq!(doppel.lok[1].uid, Some(0xc005));
+    assert_eq!(doppel.lok[1].richtung, Some(1));
+    assert_eq!(doppel.lok[1].faktor, Some(95));
+    assert!(doppel.lok[1].unknown.contains_key("neu"));
+
+    let vorspann = &body.traktion[1];
+    assert_eq!(vorspann.lok, vec![Member::new("BR 103"), Member::new("BR 218")]);
+
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), LOKOMOTIVE_CS2);
+}
+
+#[test]
+fn traktion_single_member() {
+    let cs2 = "[lokomotive]\ntraktion\n .name=Solo\n .lok\n ..name=BR 218\n ..faktor=80\n";
+    let file: File<LokomotiveFile> = serde_cs2::from_str(cs2).unwrap();
+    let member = &file.body().traktion[0].lok[0];
+    assert_eq!(member.name.as_deref(), Some("BR 218"));
+    assert_eq!(member.faktor, Some(80));
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), cs2);
+
+    // A block with nothing but the name is written as block again.
+    let cs2 = "[lokomotive]\ntraktion\n .name=Solo\n .lok\n ..name=BR 218\n";
+    let file: File<LokomotiveFile> = serde_cs2::from_str(cs2).unwrap();
+    assert!(file.body().traktion[0].lok[0].block);
+    assert_eq!(serde_cs2::to_string(&file).unwrap(), cs2);
+}
+
+#[test]
+fn traktion_resolve() {
+    let mut file: File<LokomotiveFile> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let body = file.body();
+
+    let names: Vec<_> = body.traktion[0]
+        .resolve(body)
+        .map(|(_, lokomotive)| lokomotive.map(|lokomotive| lokomotive.name.as_str()))
+        .collect();
+    assert_eq!(names, vec![Some("BR 218"), Some("V 200")]);
+    assert_eq!(body.validate_traktion(), Ok(()));
+
+    file.body_mut().traktion.push(Traktion {
+        name: "Defekt".to_owned(),
+        lok: vec![
+            Member::new("BR 218"),
+            Member::new("BR 01"),
+            Member {
+                uid: Some(0x4099),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    });
+    let body = file.body();
+    let missing: Vec<_> = body
+        .validate_traktion()
+        .unwrap_err()
+        .into_iter()
+        .map(|(traktion, member)| format!("{}: {}", traktion.name, member))
+        .collect();
+    assert_eq!(missing, vec!["Defekt: BR 01", "Defekt: 0x4099"]);
+}
@@ -0,0 +1,322 @@
+use serde_cs2::{File, Header, Value};
+use serde_derive::{Deserialize, Serialize};
+use serde_hex::{CompactPfx, SerHex, StrictPfx};
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+version
+ .minor=3
+session
+ .id=1
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .mfxuid=0x7fff8d2a
+ .adresse=0x6
+ .unbekannt=
+ .funktionen
+ ..nr=0
+ ..typ=1
+ .funktionen
+ ..nr=1
+ ..typ=131
+ ..dauer=-1
+ .blocks=0 0 0 0
+traktion
+ .name=Doppeltraktion
+ .lok=BR 218
+ .lok=BR 103
+lokomotive
+ .name=BR 103
+ .uid=0x4007
+"#;
+
+#[test]
+fn value_round_trip() {
+    let value: Value = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    assert_eq!(LOKOMOTIVE_CS2, serde_cs2::to_string(&value).unwrap());
+
+    let file = &value["[lokomotive]"];
+    assert_eq!(file["version"]["minor"].as_str(), Some("3"));
+
+    let names: Vec<_> = file
+        .get_all("lokomotive")
+        .map(|lok| lok["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["BR 218", "BR 103"]);
+
+    let lok = file.get("lokomotive").unwrap();
+    assert_eq!(lok["unbekannt"].as_str(), Some(""));
+    assert_eq!(lok["blocks"].as_str(), Some("0 0 0 0"));
+    assert_eq!(lok.get_all("funktionen").count(), 2);
+    assert_eq!(file["traktion"].get_all("lok").count(), 2);
+}
+
+#[test]
+fn value_file() {
+    let file: File<Value> = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    assert_eq!(file.header(), "lokomotive");
+    assert_eq!(file.body()["session"]["id"].as_str(), Some("1"));
+    assert_eq!(LOKOMOTIVE_CS2, serde_cs2::to_string(&file).unwrap());
+}
+
+#[test]
+fn value_serialize() {
+    let value = Value::Block(vec![(
+        "lokomotive".to_owned(),
+        Value::Block(vec![
+            ("name".to_owned(), "Lok".into()),
+            (
+                "funktionen".to_owned(),
+                Value::List(vec![
+                    Value::Block(vec![("nr".to_owned(), "0".into())]),
+                    Value::Block(vec![("nr".to_owned(), "1".into())]),
+                ]),
+            ),
+            (
+                "blocks".to_owned(),
+                Value::Array(vec!["1".to_owned(), "2".to_owned()]),
+            ),
+        ]),
+    )]);
+
+    assert_eq!(
+        "lokomotive\n .name=Lok\n .funktionen\n ..nr=0\n .funktionen\n ..nr=1\n .blocks=1 2\n",
+        serde_cs2::to_string(&value).unwrap()
+    );
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename = "lokomotive")]
+struct Lokomotive {
+    name: String,
+    funktionen: Value,
+    #[serde(flatten)]
+    unknown: std::collections::BTreeMap<String, Value>,
+}
+
+#[test]
+fn value_in_struct() {
+    let cs2 = "lokomotive\n .name=Lok\n .vmax=255\n .funktionen\n ..nr=0\n .funktionen\n ..nr=1\n";
+    let lokomotive: Vec<Lokomotive> = serde_cs2::from_str(cs2).unwrap();
+    let lokomotive = &lokomotive[0];
+    assert_eq!(lokomotive.name, "Lok");
+    assert_eq!(
+        lokomotive.funktionen,
+        Value::List(vec![
+            Value::Block(vec![("nr".to_owned(), "0".into())]),
+            Value::Block(vec![("nr".to_owned(), "1".into())]),
+        ])
+    );
+    assert_eq!(lokomotive.unknown["vmax"], Value::from("255"));
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename = "lokomotive")]
+struct TypedLokomotive {
+    name: String,
+    #[serde(with = "SerHex::<StrictPfx>")]
+    uid: u16,
+    #[serde(default, with = "SerHex::<CompactPfx>")]
+    adresse: u16,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    funktionen: Vec<Funktion>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    blocks: Option<[u8; 4]>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct Funktion {
+    nr: u8,
+    typ: u16,
+    #[serde(default)]
+    dauer: i8,
+}
+
+#[test]
+fn value_to_value() {
+    let lokomotive = TypedLokomotive {
+        name: "BR 218".to_owned(),
+        uid: 0x4006,
+        adresse: 6,
+        funktionen: vec![Funktion { nr: 0, typ: 1, dauer: 0 }],
+        blocks: None,
+    };
+
+    let value = serde_cs2::to_value(&lokomotive).unwrap();
+    assert_eq!(
+        value,
+        Value::Block(vec![
+            ("name".to_owned(), "BR 218".into()),
+            ("uid".to_owned(), "0x4006".into()),
+            ("adresse".to_owned(), "0x6".into()),
+            (
+                "funktionen".to_owned(),
+                Value::List(vec![Value::Block(vec![
+                    ("nr".to_owned(), "0".into()),
+                    ("typ".to_owned(), "1".into()),
+                    ("dauer".to_owned(), "0".into()),
+                ])]),
+            ),
+        ])
+    );
+    assert_eq!(lokomotive, serde_cs2::from_value(value).unwrap());
+
+    let file = serde_cs2::to_value(&File::new("lokomotive", Value::default())).unwrap();
+    assert_eq!(file, Value::Block(vec![("[lokomotive]".to_owned(), Value::default())]));
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+enum Richtung {
+    #[serde(rename = "vor")]
+    Vor,
+    #[serde(rename = "rueck")]
+    Rueck,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Gleise(u8, u8);
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Zugnummer(String);
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Fahrt {
+    zug: Zugnummer,
+    richtung: Richtung,
+    gleise: Gleise,
+}
+
+#[test]
+fn value_enum_round_trip() {
+    let fahrt = Fahrt {
+        zug: Zugnummer("RE 1".to_owned()),
+        richtung: Richtung::Rueck,
+        gleise: Gleise(3, 5),
+    };
+
+    let value = serde_cs2::to_value(&fahrt).unwrap();
+    assert_eq!(
+        value,
+        Value::Block(vec![
+            ("zug".to_owned(), "RE 1".into()),
+            ("richtung".to_owned(), "rueck".into()),
+            ("gleise".to_owned(), Value::Array(vec!["3".to_owned(), "5".to_owned()])),
+        ])
+    );
+    assert_eq!(fahrt, serde_cs2::from_value(value).unwrap());
+}
+
+#[test]
+fn value_patch_typed() {
+    let template: Value = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+    let template = template["[lokomotive]"].get("lokomotive").unwrap();
+
+    let lokomotive = TypedLokomotive {
+        name: "BR 120".to_owned(),
+        uid: 0x4010,
+        adresse: 10,
+        funktionen: vec![],
+        blocks: Some([1, 2, 3, 4]),
+    };
+    let mut value = serde_cs2::to_value(&lokomotive).unwrap();
+    let entries = value.as_block_mut().unwrap();
+    let typed: Vec<String> = entries.iter().map(|(key, _)| key.clone()).collect();
+    for (key, unknown) in template.as_block().unwrap() {
+        if !typed.contains(key) {
+            entries.push((key.clone(), unknown.clone()));
+        }
+    }
+
+    let patched: Vec<TypedLokomotive> =
+        serde_cs2::from_str(&serde_cs2::to_string(&Value::Block(vec![(
+            "lokomotive".to_owned(),
+            value.clone(),
+        )]))
+        .unwrap())
+        .unwrap();
+    assert_eq!(patched[0].funktionen.len(), 2);
+    assert_eq!(patched[0].blocks, Some([1, 2, 3, 4]));
+    assert_eq!(value["mfxuid"].as_str(), Some("0x7fff8d2a"));
+    assert_eq!(value["unbekannt"].as_str(), Some(""));
+}
+
+#[test]
+fn value_from_sub_tree() {
+    let value: Value = serde_cs2::from_str(LOKOMOTIVE_CS2).unwrap();
+
+    let loks: Loks = serde_cs2::from_value(value["[lokomotive]"].clone()).unwrap();
+    let lokomotive = loks.lokomotive;
+    assert_eq!(lokomotive.len(), 2);
+    assert_eq!(lokomotive[0].uid, 0x4006);
+    assert_eq!(lokomotive[0].funktionen[1].dauer, -1);
+    assert_eq!(lokomotive[1].name, "BR 103");
+
+    let funktion: Funktion = serde_cs2::from_value(lokomotive_funktion(&value)).unwrap();
+    assert_eq!(funktion, Funktion { nr: 1, typ: 131, dauer: -1 });
+
+    let file: File<Loks> = serde_cs2::from_value(value.clone()).unwrap();
+    assert_eq!(file.header(), "lokomotive");
+    assert_eq!(file.body().lokomotive.len(), 2);
+
+    let err = serde_cs2::from_value::<Funktion>(value["[lokomotive]"]["lokomotive"].clone());
+    assert!(err.is_err());
+}
+
+#[derive(Debug, Deserialize)]
+struct Loks {
+    lokomotive: Vec<TypedLokomotive>,
+}
+
+impl Header for Loks {
+    const HEADER: Option<&'static str> = Some("lokomotive");
+}
+
+fn lokomotive_funktion(value: &Value) -> Value {
+    value["[lokomotive]"]["lokomotive"]
+        .get_all("funktionen")
+        .nth(1)
+        .cloned()
+        .unwrap()
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Protokoll {
+    Mfx,
+    Dcc,
+    Mm2(u8),
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Decoder(u8, u8);
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Lok {
+    typ: Protokoll,
+    #[serde(default)]
+    decoder: Option<Decoder>,
+    #[serde(default)]
+    sekunde: Option<f32>,
+}
+
+#[test]
+fn value_from_value_scalars() {
+    let value = Value::Block(vec![
+        ("typ".to_owned(), "dcc".into()),
+        ("decoder".to_owned(), Value::Array(vec!["1".to_owned(), "2".to_owned()])),
+        ("sekunde".to_owned(), "0.5".into()),
+    ]);
+    let lok: Lok = serde_cs2::from_value(value).unwrap();
+    assert_eq!(lok, Lok { typ: Protokoll::Dcc, decoder: Some(Decoder(1, 2)), sekunde: Some(0.5) });
+    assert_eq!(serde_cs2::from_value::<Protokoll>("mfx".into()).unwrap(), Protokoll::Mfx);
+    assert_eq!(serde_cs2::from_value::<char>("x".into()).unwrap(), 'x');
+
+    let block = Value::Block(vec![("mfx".to_owned(), "1".into())]);
+    let list = Value::List(vec!["mfx".into(), "dcc".into()]);
+    for value in [block, list, "sx1".into(), "mm2".into()] {
+        assert!(serde_cs2::from_value::<Protokoll>(value).is_err());
+    }
+    assert!(serde_cs2::from_value::<f32>("schnell".into()).is_err());
+    assert!(serde_cs2::from_value::<char>("xy".into()).is_err());
+    assert!(serde_cs2::from_value::<()>("x".into()).is_err());
+}
@@ -0,0 +1,41 @@
+use serde_cs2::yaml::{self, Mapping};
+
+const LOKOMOTIVE_CS2: &str = r#"[lokomotive]
+lokomotive
+ .name=BR 218
+ .uid=0x4006
+ .funktionen
+ ..nr=0
+ .funktionen
+ ..nr=1
+"#;
+
+const LOKOMOTIVE_YAML: &str = r#"'[lokomotive]':
+  lokomotive:
+    name: BR 218
+    uid: '0x4006'
+    funktionen:
+    - nr: '0'
+    - nr: '1'
+"#;
+
+#[test]
+fn yaml_round_trip() {
+    assert_eq!(yaml::to_string(LOKOMOTIVE_CS2).unwrap(), LOKOMOTIVE_YAML);
+    assert_eq!(yaml::from_str(LOKOMOTIVE_YAML).unwrap(), LOKOMOTIVE_CS2);
+
+    let value = yaml::to_value(LOKOMOTIVE_CS2).unwrap();
+    assert_eq!(value["[lokomotive]"]["lokomotive"]["name"].as_str(), Some("BR 218"));
+    assert_eq!(yaml::from_value(&value).unwrap(), LOKOMOTIVE_CS2);
+}
+
+#[test]
+fn yaml_hex_keys() {
+    let mapping = Mapping::new().hex_keys(["uid"]);
+    let value = yaml::to_value_with(LOKOMOTIVE_CS2, &mapping).unwrap();
+    assert_eq!(value["[lokomotive]"]["lokomotive"]["uid"].as_u64(), Some(0x4006));
+
+    assert_eq!(yaml::from_value_with(&value, &mapping).unwrap(), LOKOMOTIVE_CS2);
+    let text = yaml::to_string_with(LOKOMOTIVE_CS2, &mapping).unwrap();
+    assert_eq!(yaml::from_str_with(&text, &mapping).unwrap(), LOKOMOTIVE_CS2);
+}